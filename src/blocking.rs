@@ -0,0 +1,174 @@
+//! Synchronous versions of the request methods, behind the `blocking` feature.
+//!
+//! They use reqwest's blocking client, which runs its own runtime internally, so they must not
+//! be called from within an async runtime.
+
+use super::{HttpSettings, QCError, QuickchartClient, CHART_ENDPOINT, CREATE_ENDPOINT, USER_AGENT};
+use reqwest::blocking::{Client, Response};
+use std::io::Read;
+use std::path::Path;
+use std::time::Duration;
+
+#[cfg(test)]
+#[path = "blocking_test.rs"]
+mod tests;
+
+impl HttpSettings {
+    fn build_blocking_client(&self) -> Client {
+        let mut builder = Client::builder().user_agent(USER_AGENT);
+        for certificate in &self.root_certificates {
+            builder = builder.add_root_certificate(certificate.clone());
+        }
+        if self.http1_only {
+            builder = builder.http1_only();
+        }
+        // The blocking client applies a 30 second timeout unless told otherwise
+        builder = builder.timeout(self.timeout);
+        #[cfg(feature = "gzip")]
+        {
+            builder = builder.gzip(!self.compression_disabled);
+        }
+        #[cfg(feature = "brotli")]
+        {
+            builder = builder.brotli(!self.compression_disabled);
+        }
+        builder.build().expect("Failed to create HTTP client")
+    }
+}
+
+impl QuickchartClient {
+    /// Download the chart image as bytes, like [`post()`](QuickchartClient::post) but blocking
+    /// the current thread. Requires the `blocking` feature.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use quickchart_rs::QuickchartClient;
+    ///
+    /// let image_bytes = QuickchartClient::new()
+    ///     .chart(r#"{"type":"bar","data":{"labels":["A","B"],"datasets":[{"data":[1,2]}]}}"#.to_string())
+    ///     .post_blocking()?;
+    /// # Ok::<(), quickchart_rs::QCError>(())
+    /// ```
+    pub fn post_blocking(&self) -> Result<Vec<u8>, QCError> {
+        self.send_post_request_blocking(CHART_ENDPOINT, &self.build_json_body())
+            .and_then(|response| self.read_body_blocking(response))
+            .or_else(|e| self.fallback_image_for(&e).cloned().ok_or(e))
+    }
+
+    /// Read the response body, enforcing
+    /// [`max_response_bytes()`](QuickchartClient::max_response_bytes).
+    fn read_body_blocking(&self, response: Response) -> Result<Vec<u8>, QCError> {
+        let Some(limit) = self.max_response_bytes else {
+            return Ok(response.bytes()?.to_vec());
+        };
+        if let Some(len) = response.content_length() {
+            self.check_response_size(len)?;
+        }
+        // Read one byte past the limit to tell a body of exactly `limit` bytes from a longer one
+        let mut body = Vec::new();
+        response.take(limit as u64 + 1).read_to_end(&mut body)?;
+        self.check_response_size(body.len() as u64)?;
+        Ok(body)
+    }
+
+    /// Create a short URL for the chart, like [`get_short_url()`](QuickchartClient::get_short_url)
+    /// but blocking the current thread. Requires the `blocking` feature.
+    pub fn get_short_url_blocking(&self) -> Result<String, QCError> {
+        let response = self.send_post_request_blocking(CREATE_ENDPOINT, &self.build_json_body())?;
+        let body = self.read_body_blocking(response)?;
+        Ok(self.parse_short_url_response(&body)?.url)
+    }
+
+    /// Download the chart image and save it to a file, like
+    /// [`to_file()`](QuickchartClient::to_file) but blocking the current thread, including
+    /// picking the format from the file extension. Requires the `blocking` feature.
+    pub fn to_file_blocking(&self, path: impl AsRef<Path>) -> Result<(), QCError> {
+        let image_bytes = self.for_path(path.as_ref())?.post_blocking()?;
+        std::fs::write(path, image_bytes)?;
+        Ok(())
+    }
+
+    /// The blocking counterpart of `send_post_request`, with the same fail-over and retry
+    /// behaviour.
+    fn send_post_request_blocking(
+        &self,
+        endpoint: &str,
+        json_body: &serde_json::Value,
+    ) -> Result<Response, QCError> {
+        self.check_config()?;
+        let body = serde_json::to_string(json_body)?;
+        let client = self
+            .blocking_client
+            .get_or_init(|| self.http_settings.build_blocking_client());
+
+        let mut attempt = 0;
+        loop {
+            let (result, retry_after) = self.send_to_hosts_blocking(client, endpoint, &body);
+            match result {
+                Err(ref e) if attempt < self.retries && Self::should_retry(e) => {
+                    std::thread::sleep(self.retry_delay(attempt, retry_after));
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// The blocking counterpart of `send_to_hosts`.
+    fn send_to_hosts_blocking(
+        &self,
+        client: &Client,
+        endpoint: &str,
+        body: &str,
+    ) -> (Result<Response, QCError>, Option<Duration>) {
+        let mut last_error = None;
+        let mut retry_after = None;
+
+        for base_url in std::iter::once(&self.base_url).chain(&self.fallback_base_urls) {
+            let url = match base_url.join(endpoint) {
+                Ok(url) => url,
+                Err(e) => return (Err(e.into()), None),
+            };
+            let headers = match self.custom_headers() {
+                Ok(headers) => headers,
+                Err(e) => return (Err(e), None),
+            };
+            let result = client
+                .post(url.to_string())
+                .header("Content-Type", "application/json")
+                .headers(headers)
+                .body(body.to_string())
+                .send();
+            let result = match result {
+                Ok(response) => {
+                    retry_after = Self::retry_after(response.headers());
+                    check_status(response, self.error_body_limit())
+                }
+                Err(e) => {
+                    retry_after = None;
+                    Err(e.into())
+                }
+            };
+
+            match result {
+                Ok(response) => return (Ok(response), None),
+                Err(e) if Self::should_fail_over(&e) => last_error = Some(e),
+                Err(e) => return (Err(e), retry_after),
+            }
+        }
+
+        (Err(last_error.expect("the primary base URL is always tried")), retry_after)
+    }
+}
+
+/// The blocking counterpart of `check_status`, reading at most `limit` bytes of an error body.
+fn check_status(response: Response, limit: usize) -> Result<Response, QCError> {
+    let status = response.status();
+    if !status.is_client_error() && !status.is_server_error() {
+        return Ok(response);
+    }
+    let mut body = Vec::new();
+    let _ = response.take(limit as u64).read_to_end(&mut body);
+    Err(QuickchartClient::api_error(status.as_u16(), &String::from_utf8_lossy(&body)))
+}
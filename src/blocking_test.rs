@@ -0,0 +1,103 @@
+use super::*;
+use crate::test_util::{unreachable_url, MockResponse, MockServer};
+use reqwest::Url;
+
+fn mock_client(server: &MockServer) -> QuickchartClient {
+    QuickchartClient::new()
+        .base_url(server.url())
+        .unwrap()
+        .chart(r#"{"type":"bar"}"#.to_string())
+}
+
+#[test]
+fn test_post_blocking() {
+    let server = MockServer::start(vec![MockResponse::new(200, "image")]);
+    let client = mock_client(&server).width(300);
+
+    assert_eq!(client.post_blocking().unwrap(), b"image");
+    let request = &server.requests()[0];
+    assert_eq!(request.path, "/chart");
+    assert_eq!(request.json()["width"], 300);
+    assert_eq!(request.json()["chart"]["type"], "bar");
+}
+
+#[test]
+fn test_get_short_url_blocking() {
+    let server = MockServer::start(vec![MockResponse::new(
+        200,
+        r#"{"success":true,"url":"https://quickchart.io/chart/render/abc"}"#,
+    )]);
+
+    let url = mock_client(&server).get_short_url_blocking().unwrap();
+    assert_eq!(url, "https://quickchart.io/chart/render/abc");
+    assert_eq!(server.requests()[0].path, "/chart/create");
+}
+
+#[test]
+fn test_oversized_error_body_blocking() {
+    let server = MockServer::start(vec![MockResponse::new(502, vec![b'x'; 8 * 1024 * 1024])]);
+
+    let result = mock_client(&server).max_response_bytes(100).post_blocking();
+    assert!(matches!(
+        result,
+        Err(QCError::ApiError { status: 502, ref message }) if message.len() == 100
+    ));
+}
+
+#[test]
+fn test_get_short_url_blocking_html_response() {
+    let html = "<html><body>Bad Gateway</body></html>";
+    let server = MockServer::start(vec![MockResponse::new(200, html)]);
+
+    let result = mock_client(&server).get_short_url_blocking();
+    assert!(matches!(result, Err(QCError::JsonParseError(_))));
+}
+
+#[test]
+fn test_to_file_blocking() {
+    let server = MockServer::start(vec![MockResponse::new(200, "image")]);
+    let path = std::env::temp_dir().join(format!("quickchart-blocking-{}.png", std::process::id()));
+
+    mock_client(&server).to_file_blocking(&path).unwrap();
+    assert_eq!(std::fs::read(&path).unwrap(), b"image");
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_post_blocking_fails_over() {
+    let secondary = MockServer::start(vec![MockResponse::new(200, "image")]);
+    let mut client = mock_client(&secondary)
+        .fallback_base_urls(vec![secondary.url().to_string()])
+        .unwrap();
+    client.base_url = Url::parse(&unreachable_url()).unwrap();
+
+    assert_eq!(client.post_blocking().unwrap(), b"image");
+
+    let server = MockServer::start(vec![MockResponse::new(400, "bad chart")]);
+    let error = mock_client(&server).post_blocking().unwrap_err();
+    assert!(matches!(error, QCError::ApiError { status: 400, .. }));
+}
+
+#[test]
+fn test_post_blocking_retries() {
+    let server = MockServer::start(vec![
+        MockResponse::new(503, "busy"),
+        MockResponse::new(200, "image"),
+    ]);
+    let client = mock_client(&server).retries(1).retry_base_delay(Duration::from_millis(1));
+
+    assert_eq!(client.post_blocking().unwrap(), b"image");
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[test]
+fn test_post_blocking_max_response_bytes() {
+    let server = MockServer::start(vec![
+        MockResponse::new(200, vec![0; 1000]).without_content_length(),
+        MockResponse::new(200, vec![0; 100]).without_content_length(),
+    ]);
+    let client = mock_client(&server).max_response_bytes(100);
+
+    assert!(matches!(client.post_blocking(), Err(QCError::ResponseTooLarge { limit: 100 })));
+    assert_eq!(client.post_blocking().unwrap().len(), 100);
+}
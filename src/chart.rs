@@ -0,0 +1,348 @@
+use std::fmt;
+
+use serde::ser::{SerializeMap, SerializeSeq};
+use serde::{Serialize, Serializer};
+
+/// The dataset type chartjs-chart-error-bars registers for bars with error bars.
+const ERROR_BARS_TYPE: &str = "barWithErrorBars";
+
+#[cfg(test)]
+#[path = "chart_test.rs"]
+mod tests;
+
+/// A Chart.js chart type, used by the helpers that build a chart config from data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChartType {
+    Bar,
+    Line,
+    Pie,
+    Doughnut,
+    Radar,
+    PolarArea,
+    Scatter,
+    Bubble,
+}
+
+impl ChartType {
+    /// The `type` value Chart.js expects.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ChartType::Bar => "bar",
+            ChartType::Line => "line",
+            ChartType::Pie => "pie",
+            ChartType::Doughnut => "doughnut",
+            ChartType::Radar => "radar",
+            ChartType::PolarArea => "polarArea",
+            ChartType::Scatter => "scatter",
+            ChartType::Bubble => "bubble",
+        }
+    }
+}
+
+impl fmt::Display for ChartType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for ChartType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/// A Chart.js point style, the marker drawn at each data point of a line, radar or scatter
+/// dataset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PointStyle {
+    Circle,
+    Cross,
+    CrossRot,
+    Dash,
+    Line,
+    Rect,
+    RectRounded,
+    RectRot,
+    Star,
+    Triangle,
+}
+
+impl PointStyle {
+    /// The `pointStyle` value Chart.js expects.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PointStyle::Circle => "circle",
+            PointStyle::Cross => "cross",
+            PointStyle::CrossRot => "crossRot",
+            PointStyle::Dash => "dash",
+            PointStyle::Line => "line",
+            PointStyle::Rect => "rect",
+            PointStyle::RectRounded => "rectRounded",
+            PointStyle::RectRot => "rectRot",
+            PointStyle::Star => "star",
+            PointStyle::Triangle => "triangle",
+        }
+    }
+}
+
+impl fmt::Display for PointStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for PointStyle {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/// A typed Chart.js config, for building charts without handwriting JSON. Pass it to
+/// [`chart_typed()`](crate::QuickchartClient::chart_typed); anything it doesn't cover can still
+/// be set on the client with the builder helpers or a raw config string.
+///
+/// # Example
+///
+/// ```
+/// use quickchart_rs::{Chart, ChartType, Dataset, QuickchartClient};
+///
+/// let chart = Chart::new(ChartType::Bar)
+///     .labels(vec!["Q1".to_string(), "Q2".to_string()])
+///     .dataset(Dataset::new(vec![12.0, 19.0]).label("Revenue".to_string()));
+/// let client = QuickchartClient::new().chart_typed(chart);
+///
+/// assert_eq!(
+///     client.get_chart(),
+///     r#"{"type":"bar","data":{"labels":["Q1","Q2"],"datasets":[{"label":"Revenue","data":[12.0,19.0]}]}}"#
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[must_use]
+pub struct Chart {
+    #[serde(rename = "type")]
+    chart_type: ChartType,
+    data: ChartData,
+}
+
+/// The `data` section of a [`Chart`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ChartData {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    labels: Vec<String>,
+    datasets: Vec<Dataset>,
+}
+
+/// A single series of a [`Chart`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[must_use]
+pub struct Dataset {
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    dataset_type: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+    #[serde(flatten)]
+    data: DatasetData,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    background_color: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    order: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hidden: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    point_style: Option<PointStyle>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    span_gaps: Option<bool>,
+}
+
+/// The `data` of a [`Dataset`]: plain values, or values with the low and high end of each
+/// point's error bar, written as `{"y": value, "yMin": low, "yMax": high}` points.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct DatasetData {
+    values: Vec<f64>,
+    error_bars: Option<Vec<(f64, f64)>>,
+}
+
+impl Serialize for DatasetData {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(1))?;
+        match self.error_bars {
+            Some(ref error_bars) => map.serialize_entry("data", &ErrorBarPoints(self, error_bars))?,
+            None => map.serialize_entry("data", &self.values)?,
+        }
+        map.end()
+    }
+}
+
+struct ErrorBarPoints<'a>(&'a DatasetData, &'a [(f64, f64)]);
+
+impl Serialize for ErrorBarPoints<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.1.len()))?;
+        for (value, &(low, high)) in self.0.values.iter().zip(self.1) {
+            let point = serde_json::json!({ "y": value, "yMin": low, "yMax": high });
+            seq.serialize_element(&point)?;
+        }
+        seq.end()
+    }
+}
+
+impl Chart {
+    /// An empty chart of the given type.
+    pub fn new(chart_type: ChartType) -> Self {
+        Chart {
+            chart_type,
+            data: ChartData::default(),
+        }
+    }
+
+    /// Category labels shared by every dataset.
+    pub fn labels(mut self, labels: Vec<String>) -> Self {
+        self.data.labels = labels;
+        self
+    }
+
+    /// Append a dataset.
+    pub fn dataset(mut self, dataset: Dataset) -> Self {
+        self.data.datasets.push(dataset);
+        self
+    }
+
+    pub fn get_chart_type(&self) -> ChartType {
+        self.chart_type
+    }
+
+    pub fn get_data(&self) -> &ChartData {
+        &self.data
+    }
+}
+
+impl ChartData {
+    pub fn get_labels(&self) -> &[String] {
+        &self.labels
+    }
+
+    pub fn get_datasets(&self) -> &[Dataset] {
+        &self.datasets
+    }
+}
+
+impl Dataset {
+    /// A dataset with the given values, one per label.
+    pub fn new(data: Vec<f64>) -> Self {
+        Dataset {
+            data: DatasetData {
+                values: data,
+                error_bars: None,
+            },
+            ..Default::default()
+        }
+    }
+
+    /// A bar dataset with an error bar on each point, given as `(value, low, high)`.
+    ///
+    /// Rendering needs the [chartjs-chart-error-bars] plugin, which QuickChart bundles: the
+    /// dataset's type is set to the plugin's `barWithErrorBars`, which turns it on, and each
+    /// point is written as `{"y": value, "yMin": low, "yMax": high}`. Use it in a
+    /// [`ChartType::Bar`] chart.
+    ///
+    /// [chartjs-chart-error-bars]: https://github.com/sgratzl/chartjs-chart-error-bars
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use quickchart_rs::{Chart, ChartType, Dataset};
+    ///
+    /// let chart = Chart::new(ChartType::Bar)
+    ///     .labels(vec!["Trial 1".to_string(), "Trial 2".to_string()])
+    ///     .dataset(Dataset::with_error_bars(vec![(4.2, 3.9, 4.6), (5.1, 4.4, 5.5)]));
+    /// ```
+    pub fn with_error_bars(points: Vec<(f64, f64, f64)>) -> Self {
+        let (values, error_bars) = points
+            .into_iter()
+            .map(|(value, low, high)| (value, (low, high)))
+            .unzip();
+        Dataset {
+            dataset_type: Some(ERROR_BARS_TYPE),
+            data: DatasetData {
+                values,
+                error_bars: Some(error_bars),
+            },
+            ..Default::default()
+        }
+    }
+
+    /// Name shown in the legend and tooltips.
+    pub fn label(mut self, label: String) -> Self {
+        self.label = Some(label);
+        self
+    }
+
+    /// Fill color, as any CSS color string.
+    pub fn background_color(mut self, color: String) -> Self {
+        self.background_color = Some(color);
+        self
+    }
+
+    /// Drawing and stacking order relative to the other datasets. Datasets with a lower order
+    /// are drawn on top, e.g. give a line `order(0)` and the bars `order(1)` to keep the line
+    /// above them whatever order the datasets were added in. Chart.js defaults to `0`.
+    pub fn order(mut self, order: i32) -> Self {
+        self.order = Some(order);
+        self
+    }
+
+    /// Start with the dataset hidden: it's listed, struck through, in the legend but not drawn.
+    pub fn hidden(mut self, hidden: bool) -> Self {
+        self.hidden = Some(hidden);
+        self
+    }
+
+    /// Marker drawn at each point, e.g. to tell series apart in monochrome print.
+    pub fn point_style(mut self, point_style: PointStyle) -> Self {
+        self.point_style = Some(point_style);
+        self
+    }
+
+    /// For line datasets, whether to draw the line across `null` or missing points (`true`) or
+    /// leave a gap (`false`, Chart.js's default). Overrides the chart-level
+    /// [`span_gaps()`](crate::QuickchartClient::span_gaps) for this dataset.
+    pub fn span_gaps(mut self, span_gaps: bool) -> Self {
+        self.span_gaps = Some(span_gaps);
+        self
+    }
+
+    pub fn get_label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    pub fn get_data(&self) -> &[f64] {
+        &self.data.values
+    }
+
+    /// The `(low, high)` error bar of each point, if the dataset was built with
+    /// [`with_error_bars()`](Dataset::with_error_bars).
+    pub fn get_error_bars(&self) -> Option<&[(f64, f64)]> {
+        self.data.error_bars.as_deref()
+    }
+
+    pub fn get_background_color(&self) -> Option<&str> {
+        self.background_color.as_deref()
+    }
+
+    pub fn get_order(&self) -> Option<i32> {
+        self.order
+    }
+
+    pub fn get_hidden(&self) -> Option<bool> {
+        self.hidden
+    }
+
+    pub fn get_point_style(&self) -> Option<PointStyle> {
+        self.point_style
+    }
+
+    pub fn get_span_gaps(&self) -> Option<bool> {
+        self.span_gaps
+    }
+}
@@ -0,0 +1,401 @@
+use crate::data_labels::DataLabels;
+use crate::number_format::NumberFormat;
+use crate::raw_js::RawJs;
+use serde_json::{Map, Value};
+
+/// Extra bottom padding, in pixels, added for x-axis labels rotated to 90 degrees. Smaller
+/// rotations get a proportional share.
+const ROTATED_LABEL_PADDING: f64 = 30.0;
+
+/// Extra bottom padding, in pixels, that makes room for the Chart.js 2 footer.
+const FOOTER_PADDING: f64 = 20.0;
+
+/// Colors assigned to datasets by `assign_colors`, in order (Chart.js's documentation palette).
+const DATASET_PALETTE: &[&str] = &[
+    "rgb(54, 162, 235)",
+    "rgb(255, 99, 132)",
+    "rgb(255, 159, 64)",
+    "rgb(255, 205, 86)",
+    "rgb(75, 192, 192)",
+    "rgb(153, 102, 255)",
+    "rgb(201, 203, 207)",
+];
+
+/// Chart.js option overrides set through the client's builder helpers.
+///
+/// Overrides are merged into JSON chart configs when a request is built, so they apply
+/// regardless of whether the helper was called before or after [`chart()`](crate::QuickchartClient::chart).
+/// JS-notation configs can't be merged into and are sent unchanged.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct ChartOptions {
+    pub(crate) fixed_size: bool,
+    pub(crate) keep_animation: bool,
+    pub(crate) data_labels: Option<DataLabels>,
+    pub(crate) grid_color: Option<String>,
+    pub(crate) grid_dash: Option<Vec<u32>>,
+    pub(crate) label_rotation: Option<i32>,
+    pub(crate) assign_colors: bool,
+    pub(crate) x_tick_step: Option<f64>,
+    pub(crate) y_tick_step: Option<f64>,
+    pub(crate) x_max_ticks: Option<u32>,
+    pub(crate) y_max_ticks: Option<u32>,
+    pub(crate) legend_only: bool,
+    pub(crate) color_scheme: Option<String>,
+    pub(crate) number_format: Option<NumberFormat>,
+    pub(crate) plot_background: Option<String>,
+    pub(crate) span_gaps: Option<bool>,
+    pub(crate) legend_point_style: Option<bool>,
+    pub(crate) legend_box_width: Option<u32>,
+    pub(crate) time_x_axis: bool,
+    pub(crate) footer: Option<String>,
+    pub(crate) title: Option<String>,
+    pub(crate) x_axis_label: Option<String>,
+    pub(crate) y_axis_label: Option<String>,
+    pub(crate) sparkline: bool,
+    pub(crate) chart_type: Option<String>,
+    pub(crate) labels: Option<Vec<String>>,
+    pub(crate) datasets: Vec<(String, Vec<f64>)>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Axis {
+    X,
+    Y,
+}
+
+impl ChartOptions {
+    /// Merge all configured overrides into `chart`. `version` is the Chart.js version sent to
+    /// QuickChart and selects between the Chart.js 2 and 3+ option layouts.
+    pub(crate) fn apply(&self, chart: &mut Value, version: Option<&str>) {
+        let major = chart_js_major_version(version);
+
+        if let Some(ref chart_type) = self.chart_type {
+            merge_at(chart, &["type"], Value::String(chart_type.clone()));
+        }
+        if let Some(ref labels) = self.labels {
+            merge_at(chart, &["data", "labels"], serde_json::json!(labels));
+        }
+        if !self.datasets.is_empty() {
+            append_datasets(chart, &self.datasets);
+        }
+        if !self.keep_animation {
+            merge_at(chart, &["options", "animation"], Value::Bool(false));
+        }
+        if self.fixed_size {
+            merge_at(chart, &["options", "responsive"], Value::Bool(false));
+            merge_at(chart, &["options", "maintainAspectRatio"], Value::Bool(false));
+        }
+        if let Some(span_gaps) = self.span_gaps {
+            merge_at(chart, &["options", "spanGaps"], Value::Bool(span_gaps));
+        }
+        let legend_labels = |key| [legend_path(major), &["labels", key]].concat();
+        if let Some(point_style) = self.legend_point_style {
+            merge_at(chart, &legend_labels("usePointStyle"), Value::Bool(point_style));
+        }
+        if let Some(box_width) = self.legend_box_width {
+            merge_at(chart, &legend_labels("boxWidth"), box_width.into());
+        }
+        if let Some(ref data_labels) = self.data_labels {
+            if let Value::Object(labels) = data_labels.to_value() {
+                for (key, value) in labels {
+                    merge_at(chart, &["options", "plugins", "datalabels", &key], value);
+                }
+            }
+        }
+        if let Some(ref number_format) = self.number_format {
+            let callback = number_format.to_raw_js().to_value();
+            merge_axis(chart, major, Axis::Y, &["ticks", "callback"], callback.clone());
+            let labels = chart.pointer("/options/plugins/datalabels");
+            if labels.is_some_and(|labels| labels.is_object() && labels.get("formatter").is_none()) {
+                merge_at(chart, &["options", "plugins", "datalabels", "formatter"], callback);
+            }
+        }
+        for axis in [Axis::X, Axis::Y] {
+            if let Some(ref color) = self.grid_color {
+                let path: &[&str] = if major < 3 { &["gridLines", "color"] } else { &["grid", "color"] };
+                merge_axis(chart, major, axis, path, Value::String(color.clone()));
+            }
+            if let Some(ref dash) = self.grid_dash {
+                let path: &[&str] = match major {
+                    ..=2 => &["gridLines", "borderDash"],
+                    3 => &["grid", "borderDash"],
+                    _ => &["border", "dash"],
+                };
+                merge_axis(chart, major, axis, path, serde_json::json!(dash));
+            }
+        }
+        if self.time_x_axis {
+            merge_axis(chart, major, Axis::X, &["type"], Value::String("time".to_string()));
+        }
+        for (axis, step, max_ticks) in [
+            (Axis::X, self.x_tick_step, self.x_max_ticks),
+            (Axis::Y, self.y_tick_step, self.y_max_ticks),
+        ] {
+            if let Some(step) = step {
+                merge_axis(chart, major, axis, &["ticks", "stepSize"], serde_json::json!(step));
+            }
+            if let Some(max_ticks) = max_ticks {
+                merge_axis(chart, major, axis, &["ticks", "maxTicksLimit"], max_ticks.into());
+            }
+        }
+        if let Some(degrees) = self.label_rotation {
+            merge_axis(chart, major, Axis::X, &["ticks", "maxRotation"], degrees.into());
+            merge_axis(chart, major, Axis::X, &["ticks", "minRotation"], degrees.into());
+
+            let extra = ROTATED_LABEL_PADDING * degrees.unsigned_abs().min(90) as f64 / 90.0;
+            add_bottom_padding(chart, extra.round());
+        }
+        if let Some(ref scheme) = self.color_scheme {
+            let path = ["options", "plugins", "colorschemes", "scheme"];
+            merge_at(chart, &path, Value::String(scheme.clone()));
+        }
+        if let Some(ref color) = self.plot_background {
+            add_inline_plugin(chart, plot_background_plugin(color));
+        }
+        if let Some(ref text) = self.title {
+            merge_at(chart, &[title_path(major), &["display"]].concat(), Value::Bool(true));
+            merge_at(chart, &[title_path(major), &["text"]].concat(), Value::String(text.clone()));
+        }
+        for (axis, label) in [(Axis::X, &self.x_axis_label), (Axis::Y, &self.y_axis_label)] {
+            let Some(label) = label else { continue };
+            let (parent, key) =
+                if major < 3 { ("scaleLabel", "labelString") } else { ("title", "text") };
+            merge_axis(chart, major, axis, &[parent, "display"], Value::Bool(true));
+            merge_axis(chart, major, axis, &[parent, key], Value::String(label.clone()));
+        }
+        if let Some(ref text) = self.footer {
+            if major < 3 {
+                add_inline_plugin(chart, footer_plugin(text));
+                add_bottom_padding(chart, FOOTER_PADDING);
+            } else {
+                let subtitle =
+                    serde_json::json!({ "display": true, "text": text, "position": "bottom" });
+                merge_at(chart, &["options", "plugins", "subtitle"], subtitle);
+            }
+        }
+        if self.assign_colors {
+            assign_dataset_colors(chart);
+        }
+        if self.sparkline {
+            strip_to_plot(chart, major);
+        }
+        if self.legend_only {
+            strip_to_legend(chart, major);
+        }
+    }
+
+    /// Whether the helpers set enough of the chart (type or data) to build a config from
+    /// scratch when no chart has been set.
+    pub(crate) fn builds_chart(&self) -> bool {
+        self.chart_type.is_some() || self.labels.is_some() || !self.datasets.is_empty()
+    }
+}
+
+/// Append a dataset for each `(label, data)` pair to `data.datasets`, after any datasets
+/// already in the config. Non-finite values become `null`, which Chart.js draws as a gap.
+fn append_datasets(chart: &mut Value, datasets: &[(String, Vec<f64>)]) {
+    let data = object_mut(object_mut(chart).entry("data").or_insert(Value::Null));
+    let existing = data.entry("datasets").or_insert_with(|| Value::Array(Vec::new()));
+    if !existing.is_array() {
+        *existing = Value::Array(Vec::new());
+    }
+    let existing = existing.as_array_mut().expect("datasets was just made an array");
+    for (label, values) in datasets {
+        existing.push(serde_json::json!({ "label": label, "data": values }));
+    }
+}
+
+/// Give every dataset without a `backgroundColor` or `borderColor` an explicit color from
+/// [`DATASET_PALETTE`], based on its position.
+fn assign_dataset_colors(chart: &mut Value) {
+    let Some(datasets) = chart.pointer_mut("/data/datasets").and_then(Value::as_array_mut) else {
+        return;
+    };
+    for (i, dataset) in datasets.iter_mut().enumerate() {
+        let Some(dataset) = dataset.as_object_mut() else { continue };
+        let color = Value::String(DATASET_PALETTE[i % DATASET_PALETTE.len()].to_string());
+        for key in ["backgroundColor", "borderColor"] {
+            dataset.entry(key).or_insert_with(|| color.clone());
+        }
+    }
+}
+
+/// An inline Chart.js plugin that fills the chart area (inside the axes) with `color` before
+/// anything else is drawn on it. `chartArea` has the same shape in Chart.js 2 through 4.
+fn plot_background_plugin(color: &str) -> Value {
+    let before_draw = format!(
+        "function(chart) {{ var area = chart.chartArea; var ctx = chart.ctx; ctx.save(); \
+         ctx.fillStyle = {}; \
+         ctx.fillRect(area.left, area.top, area.right - area.left, area.bottom - area.top); \
+         ctx.restore(); }}",
+        Value::String(color.to_string())
+    );
+    serde_json::json!({
+        "id": "plotBackground",
+        "beforeDraw": RawJs::new(before_draw).to_value(),
+    })
+}
+
+/// An inline Chart.js 2 plugin that writes `text` centered along the bottom edge of the canvas,
+/// since Chart.js 2 has no subtitle.
+fn footer_plugin(text: &str) -> Value {
+    let after_draw = format!(
+        "function(chart) {{ var ctx = chart.ctx; ctx.save(); ctx.font = '12px sans-serif'; \
+         ctx.fillStyle = '#666'; ctx.textAlign = 'center'; ctx.textBaseline = 'bottom'; \
+         ctx.fillText({}, chart.width / 2, chart.height - 4); ctx.restore(); }}",
+        Value::String(text.to_string())
+    );
+    serde_json::json!({
+        "id": "footer",
+        "afterDraw": RawJs::new(after_draw).to_value(),
+    })
+}
+
+/// Append `plugin` to the config's top-level `plugins` array, creating it if needed.
+fn add_inline_plugin(chart: &mut Value, plugin: Value) {
+    match object_mut(chart).get_mut("plugins") {
+        Some(Value::Array(plugins)) => plugins.push(plugin),
+        _ => merge_at(chart, &["plugins"], Value::Array(vec![plugin])),
+    }
+}
+
+/// Reduce the chart to its legend: every dataset keeps its label and colors but loses its data,
+/// both axes are hidden, and the legend is forced on with tooltips off.
+fn strip_to_legend(chart: &mut Value, major: u32) {
+    if let Some(datasets) = chart.pointer_mut("/data/datasets").and_then(Value::as_array_mut) {
+        for dataset in datasets.iter_mut().filter(|dataset| dataset.is_object()) {
+            dataset["data"] = Value::Array(Vec::new());
+        }
+    }
+    for axis in [Axis::X, Axis::Y] {
+        merge_axis(chart, major, axis, &["display"], Value::Bool(false));
+    }
+    merge_at(chart, &[legend_path(major), &["display"]].concat(), Value::Bool(true));
+    merge_at(chart, tooltip_enabled_path(major), Value::Bool(false));
+}
+
+/// Hide everything but the plotted line: axes, legend, tooltips and point markers.
+fn strip_to_plot(chart: &mut Value, major: u32) {
+    for axis in [Axis::X, Axis::Y] {
+        merge_axis(chart, major, axis, &["display"], Value::Bool(false));
+    }
+    merge_at(chart, &[legend_path(major), &["display"]].concat(), Value::Bool(false));
+    merge_at(chart, tooltip_enabled_path(major), Value::Bool(false));
+    merge_at(chart, &["options", "elements", "point", "radius"], 0.into());
+}
+
+/// Where tooltips are switched on and off: `options.tooltips` in Chart.js 2,
+/// `options.plugins.tooltip` from Chart.js 3.
+fn tooltip_enabled_path(major: u32) -> &'static [&'static str] {
+    if major < 3 {
+        &["options", "tooltips", "enabled"]
+    } else {
+        &["options", "plugins", "tooltip", "enabled"]
+    }
+}
+
+/// Where the legend options live: `options.legend` in Chart.js 2, `options.plugins.legend` from
+/// Chart.js 3.
+fn legend_path(major: u32) -> &'static [&'static str] {
+    if major < 3 {
+        &["options", "legend"]
+    } else {
+        &["options", "plugins", "legend"]
+    }
+}
+
+/// Where the title options live: `options.title` in Chart.js 2, `options.plugins.title` from
+/// Chart.js 3.
+fn title_path(major: u32) -> &'static [&'static str] {
+    if major < 3 {
+        &["options", "title"]
+    } else {
+        &["options", "plugins", "title"]
+    }
+}
+
+/// Increase `options.layout.padding.bottom` by `extra` pixels, expanding a uniform numeric
+/// padding into per-side values first.
+fn add_bottom_padding(chart: &mut Value, extra: f64) {
+    let padding = chart.pointer("/options/layout/padding").cloned();
+    let padding = match padding {
+        Some(Value::Number(uniform)) => ["top", "right", "bottom", "left"]
+            .into_iter()
+            .map(|side| (side.to_string(), Value::Number(uniform.clone())))
+            .collect(),
+        Some(padding @ Value::Object(_)) => padding,
+        _ => Value::Object(Map::new()),
+    };
+    let bottom = padding.get("bottom").and_then(Value::as_f64).unwrap_or(0.0);
+
+    merge_at(chart, &["options", "layout", "padding"], padding);
+    merge_at(chart, &["options", "layout", "padding", "bottom"], serde_json::json!(bottom + extra));
+}
+
+/// The Chart.js major version QuickChart renders with. QuickChart defaults to Chart.js 2 when
+/// no version is given.
+fn chart_js_major_version(version: Option<&str>) -> u32 {
+    version
+        .map(|v| v.trim_start_matches(['v', 'V']))
+        .and_then(|v| v.split('.').next())
+        .and_then(|major| major.parse().ok())
+        .unwrap_or(2)
+}
+
+/// Merge `value` at `path` within the config of `axis`. Chart.js 3+ keeps each axis under
+/// `scales.x` / `scales.y`; Chart.js 2 uses the `scales.xAxes` / `scales.yAxes` arrays, in which
+/// case every axis in the array is updated, creating one if the array is missing or empty.
+fn merge_axis(chart: &mut Value, major: u32, axis: Axis, path: &[&str], value: Value) {
+    if major >= 3 {
+        let id = match axis {
+            Axis::X => "x",
+            Axis::Y => "y",
+        };
+        let full_path: Vec<&str> = ["options", "scales", id].iter().chain(path).copied().collect();
+        merge_at(chart, &full_path, value);
+        return;
+    }
+
+    let id = match axis {
+        Axis::X => "xAxes",
+        Axis::Y => "yAxes",
+    };
+    let mut axes = chart.pointer_mut(&format!("/options/scales/{}", id));
+    if !axes.as_ref().is_some_and(|axes| axes.is_array()) {
+        merge_at(chart, &["options", "scales", id], Value::Array(Vec::new()));
+        axes = chart.pointer_mut(&format!("/options/scales/{}", id));
+    }
+    let axes = axes
+        .and_then(Value::as_array_mut)
+        .expect("axes was just made an array");
+    if axes.is_empty() {
+        axes.push(Value::Object(Map::new()));
+    }
+    for axis_config in axes {
+        merge_at(axis_config, path, value.clone());
+    }
+}
+
+/// Set `value` at `path`, creating intermediate objects as needed and leaving sibling keys
+/// untouched. Intermediate values that aren't objects are replaced.
+pub(crate) fn merge_at(target: &mut Value, path: &[&str], value: Value) {
+    let Some((last, parents)) = path.split_last() else {
+        *target = value;
+        return;
+    };
+
+    let mut current = target;
+    for key in parents {
+        current = object_mut(current)
+            .entry(key.to_string())
+            .or_insert_with(|| Value::Object(Map::new()));
+    }
+    object_mut(current).insert(last.to_string(), value);
+}
+
+fn object_mut(value: &mut Value) -> &mut Map<String, Value> {
+    if !value.is_object() {
+        *value = Value::Object(Map::new());
+    }
+    value.as_object_mut().expect("value was just made an object")
+}
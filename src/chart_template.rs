@@ -0,0 +1,54 @@
+/// A chart saved on QuickChart as a template, rendered by ID from `/chart/render/{id}` with some
+/// of its values replaced. Render it with
+/// [`template_url()`](crate::QuickchartClient::template_url) or
+/// [`render_template()`](crate::QuickchartClient::render_template), which add the client's
+/// dimensions, background, device pixel ratio and format.
+///
+/// Parameters are passed to QuickChart unchanged; see its template documentation for the names
+/// it understands, such as `title`, `labels` or `data1`.
+///
+/// # Example
+///
+/// ```
+/// use quickchart_rs::{ChartTemplate, QuickchartClient};
+///
+/// let template = ChartTemplate::new("zf-abc-123".to_string())
+///     .param("title".to_string(), "Weekly signups".to_string())
+///     .param("data1".to_string(), "5,8,13".to_string());
+/// let url = QuickchartClient::new().width(600).template_url(&template).unwrap();
+///
+/// assert_eq!(
+///     url,
+///     "https://quickchart.io/chart/render/zf-abc-123?title=Weekly+signups&data1=5%2C8%2C13&w=600"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[must_use]
+pub struct ChartTemplate {
+    id: String,
+    params: Vec<(String, String)>,
+}
+
+impl ChartTemplate {
+    /// The template with the given ID, as shown in its short URL.
+    pub fn new(id: String) -> Self {
+        ChartTemplate {
+            id,
+            params: Vec::new(),
+        }
+    }
+
+    /// Replace a template value. Parameters are sent in the order they're added.
+    pub fn param(mut self, key: String, value: String) -> Self {
+        self.params.push((key, value));
+        self
+    }
+
+    pub fn get_id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn get_params(&self) -> &[(String, String)] {
+        &self.params
+    }
+}
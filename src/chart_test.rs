@@ -0,0 +1,123 @@
+use super::*;
+
+#[test]
+fn test_chart_serializes_to_chart_js_config() {
+    let chart = Chart::new(ChartType::Line)
+        .labels(vec!["Jan".to_string(), "Feb".to_string()])
+        .dataset(
+            Dataset::new(vec![1.0, 2.5])
+                .label("Visits".to_string())
+                .background_color("rgba(54, 162, 235, 0.5)".to_string()),
+        )
+        .dataset(Dataset::new(vec![3.0, 4.0]));
+
+    assert_eq!(
+        serde_json::to_value(&chart).unwrap(),
+        serde_json::json!({
+            "type": "line",
+            "data": {
+                "labels": ["Jan", "Feb"],
+                "datasets": [
+                    {
+                        "label": "Visits",
+                        "data": [1.0, 2.5],
+                        "backgroundColor": "rgba(54, 162, 235, 0.5)"
+                    },
+                    { "data": [3.0, 4.0] }
+                ]
+            }
+        })
+    );
+}
+
+#[test]
+fn test_chart_type_serializes_as_chart_js_name() {
+    assert_eq!(serde_json::to_value(ChartType::PolarArea).unwrap(), "polarArea");
+
+    let chart = serde_json::to_value(Chart::new(ChartType::Pie)).unwrap();
+    assert_eq!(chart, serde_json::json!({ "type": "pie", "data": { "datasets": [] } }));
+}
+
+#[test]
+fn test_dataset_order() {
+    let chart = Chart::new(ChartType::Bar)
+        .dataset(Dataset::new(vec![1.0]).order(1))
+        .dataset(Dataset::new(vec![2.0]).order(0))
+        .dataset(Dataset::new(vec![3.0]));
+
+    let datasets = &serde_json::to_value(&chart).unwrap()["data"]["datasets"];
+    assert_eq!(datasets[0]["order"], 1);
+    assert_eq!(datasets[1]["order"], 0);
+    assert!(datasets[2].get("order").is_none());
+    assert_eq!(chart.get_data().get_datasets()[0].get_order(), Some(1));
+}
+
+#[test]
+fn test_dataset_hidden() {
+    let chart = Chart::new(ChartType::Line)
+        .dataset(Dataset::new(vec![1.0]).hidden(true))
+        .dataset(Dataset::new(vec![2.0]));
+
+    let datasets = &serde_json::to_value(&chart).unwrap()["data"]["datasets"];
+    assert_eq!(datasets[0], serde_json::json!({ "data": [1.0], "hidden": true }));
+    assert!(datasets[1].get("hidden").is_none());
+}
+
+#[test]
+fn test_dataset_with_error_bars() {
+    let dataset = Dataset::with_error_bars(vec![(4.0, 3.5, 4.5), (6.0, 5.0, 7.5)])
+        .label("Mean".to_string());
+    assert_eq!(dataset.get_data(), &[4.0, 6.0]);
+    assert_eq!(dataset.get_error_bars(), Some(&[(3.5, 4.5), (5.0, 7.5)][..]));
+
+    let chart = Chart::new(ChartType::Bar)
+        .labels(vec!["A".to_string(), "B".to_string()])
+        .dataset(dataset);
+    assert_eq!(
+        serde_json::to_value(&chart).unwrap()["data"]["datasets"][0],
+        serde_json::json!({
+            "type": "barWithErrorBars",
+            "label": "Mean",
+            "data": [
+                { "y": 4.0, "yMin": 3.5, "yMax": 4.5 },
+                { "y": 6.0, "yMin": 5.0, "yMax": 7.5 }
+            ]
+        })
+    );
+    assert!(Dataset::new(vec![1.0]).get_error_bars().is_none());
+}
+
+#[test]
+fn test_dataset_point_style() {
+    let styles = [
+        (PointStyle::Circle, "circle"),
+        (PointStyle::Cross, "cross"),
+        (PointStyle::CrossRot, "crossRot"),
+        (PointStyle::Dash, "dash"),
+        (PointStyle::Line, "line"),
+        (PointStyle::Rect, "rect"),
+        (PointStyle::RectRounded, "rectRounded"),
+        (PointStyle::RectRot, "rectRot"),
+        (PointStyle::Star, "star"),
+        (PointStyle::Triangle, "triangle"),
+    ];
+    for (style, token) in styles {
+        assert_eq!(style.to_string(), token);
+        let dataset = serde_json::to_value(Dataset::new(vec![1.0]).point_style(style)).unwrap();
+        assert_eq!(dataset["pointStyle"], token);
+    }
+    assert!(serde_json::to_value(Dataset::new(vec![1.0])).unwrap().get("pointStyle").is_none());
+}
+
+#[test]
+fn test_dataset_span_gaps() {
+    let chart = Chart::new(ChartType::Line)
+        .dataset(Dataset::new(vec![1.0, 3.0]).span_gaps(true))
+        .dataset(Dataset::new(vec![2.0]).span_gaps(false))
+        .dataset(Dataset::new(vec![4.0]));
+
+    let datasets = &serde_json::to_value(&chart).unwrap()["data"]["datasets"];
+    assert_eq!(datasets[0]["spanGaps"], true);
+    assert_eq!(datasets[1]["spanGaps"], false);
+    assert!(datasets[2].get("spanGaps").is_none());
+}
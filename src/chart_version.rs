@@ -0,0 +1,30 @@
+use std::fmt;
+
+/// A Chart.js major version QuickChart can render with, for
+/// [`version_typed()`](crate::QuickchartClient::version_typed).
+///
+/// Chart.js 2 and 3+ lay out options differently (e.g. `options.legend` moved to
+/// `options.plugins.legend`), so the config has to be written for the version picked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChartVersion {
+    V2,
+    V3,
+    V4,
+}
+
+impl ChartVersion {
+    /// The `v` / `version` value QuickChart expects.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ChartVersion::V2 => "2",
+            ChartVersion::V3 => "3",
+            ChartVersion::V4 => "4",
+        }
+    }
+}
+
+impl fmt::Display for ChartVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
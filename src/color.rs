@@ -0,0 +1,96 @@
+//! Client-side checking of CSS color strings, for
+//! [`background_color_checked()`](crate::QuickchartClient::background_color_checked).
+
+#[cfg(test)]
+#[path = "color_test.rs"]
+mod tests;
+
+/// CSS named colors, plus `transparent`.
+const NAMED_COLORS: &[&str] = &[
+    "aliceblue", "antiquewhite", "aqua", "aquamarine", "azure", "beige", "bisque", "black",
+    "blanchedalmond", "blue", "blueviolet", "brown", "burlywood", "cadetblue", "chartreuse",
+    "chocolate", "coral", "cornflowerblue", "cornsilk", "crimson", "cyan", "darkblue", "darkcyan",
+    "darkgoldenrod", "darkgray", "darkgreen", "darkgrey", "darkkhaki", "darkmagenta",
+    "darkolivegreen", "darkorange", "darkorchid", "darkred", "darksalmon", "darkseagreen",
+    "darkslateblue", "darkslategray", "darkslategrey", "darkturquoise", "darkviolet", "deeppink",
+    "deepskyblue", "dimgray", "dimgrey", "dodgerblue", "firebrick", "floralwhite", "forestgreen",
+    "fuchsia", "gainsboro", "ghostwhite", "gold", "goldenrod", "gray", "green", "greenyellow",
+    "grey", "honeydew", "hotpink", "indianred", "indigo", "ivory", "khaki", "lavender",
+    "lavenderblush", "lawngreen", "lemonchiffon", "lightblue", "lightcoral", "lightcyan",
+    "lightgoldenrodyellow", "lightgray", "lightgreen", "lightgrey", "lightpink", "lightsalmon",
+    "lightseagreen", "lightskyblue", "lightslategray", "lightslategrey", "lightsteelblue",
+    "lightyellow", "lime", "limegreen", "linen", "magenta", "maroon", "mediumaquamarine",
+    "mediumblue", "mediumorchid", "mediumpurple", "mediumseagreen", "mediumslateblue",
+    "mediumspringgreen", "mediumturquoise", "mediumvioletred", "midnightblue", "mintcream",
+    "mistyrose", "moccasin", "navajowhite", "navy", "oldlace", "olive", "olivedrab", "orange",
+    "orangered", "orchid", "palegoldenrod", "palegreen", "paleturquoise", "palevioletred",
+    "papayawhip", "peachpuff", "peru", "pink", "plum", "powderblue", "purple", "rebeccapurple",
+    "red", "rosybrown", "royalblue", "saddlebrown", "salmon", "sandybrown", "seagreen",
+    "seashell", "sienna", "silver", "skyblue", "slateblue", "slategray", "slategrey", "snow",
+    "springgreen", "steelblue", "tan", "teal", "thistle", "tomato", "transparent", "turquoise",
+    "violet", "wheat", "white", "whitesmoke", "yellow", "yellowgreen",
+];
+
+/// Whether `color` is a named color, a `#` hex color of 3, 4, 6 or 8 digits, or an `rgb()`,
+/// `rgba()`, `hsl()` or `hsla()` color with in-range components. Case is ignored.
+pub(crate) fn is_valid_color(color: &str) -> bool {
+    let color = color.trim().to_ascii_lowercase();
+    if let Some(hex) = color.strip_prefix('#') {
+        return matches!(hex.len(), 3 | 4 | 6 | 8) && hex.chars().all(|ch| ch.is_ascii_hexdigit());
+    }
+    if let Some((name, args)) = color.strip_suffix(')').and_then(|color| color.split_once('(')) {
+        let Some((channels, alpha)) = split_args(args) else {
+            return false;
+        };
+        let channels_valid = match (name.trim_end(), channels.as_slice()) {
+            ("rgb" | "rgba", [r, g, b]) => [r, g, b].iter().all(|channel| is_rgb_channel(channel)),
+            ("hsl" | "hsla", [h, s, l]) => is_hue(h) && is_percentage(s) && is_percentage(l),
+            _ => false,
+        };
+        return channels_valid && alpha.is_none_or(is_alpha);
+    }
+    NAMED_COLORS.contains(&color.as_str())
+}
+
+/// Split color function arguments into the three channels and the optional alpha. Accepts the
+/// comma-separated form (`255, 0, 0, 0.5`) and the space-separated one with a slash before the
+/// alpha (`255 0 0 / 50%`).
+fn split_args(args: &str) -> Option<(Vec<&str>, Option<&str>)> {
+    let (mut channels, alpha): (Vec<&str>, _) = if args.contains(',') {
+        (args.split(',').map(str::trim).collect(), None)
+    } else {
+        match args.split_once('/') {
+            Some((channels, alpha)) => (channels.split_whitespace().collect(), Some(alpha.trim())),
+            None => (args.split_whitespace().collect(), None),
+        }
+    };
+    let alpha = match (channels.len(), alpha) {
+        (3, alpha) => alpha,
+        (4, None) if args.contains(',') => channels.pop(),
+        _ => return None,
+    };
+    Some((channels, alpha))
+}
+
+fn number(value: &str) -> Option<f64> {
+    value.parse::<f64>().ok().filter(|number| number.is_finite())
+}
+
+fn is_percentage(value: &str) -> bool {
+    value
+        .strip_suffix('%')
+        .and_then(number)
+        .is_some_and(|percent| (0.0..=100.0).contains(&percent))
+}
+
+fn is_rgb_channel(value: &str) -> bool {
+    is_percentage(value) || number(value).is_some_and(|channel| (0.0..=255.0).contains(&channel))
+}
+
+fn is_hue(value: &str) -> bool {
+    number(value.strip_suffix("deg").unwrap_or(value)).is_some()
+}
+
+fn is_alpha(value: &str) -> bool {
+    is_percentage(value) || number(value).is_some_and(|alpha| (0.0..=1.0).contains(&alpha))
+}
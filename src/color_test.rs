@@ -0,0 +1,49 @@
+use super::*;
+
+#[test]
+fn test_valid_colors() {
+    for color in [
+        "white",
+        "Transparent",
+        "rebeccapurple",
+        "#fff",
+        "#FFFA",
+        "#1f4e79",
+        "#1f4e7980",
+        "rgb(255, 0, 0)",
+        "rgb(100%, 50%, 0%)",
+        "rgb(255 0 0)",
+        "rgb(255 0 0 / 50%)",
+        "rgba(54, 162, 235, 0.5)",
+        "RGBA(0,0,0,1)",
+        "hsl(0, 100%, 50%)",
+        "hsl(210deg 50% 40%)",
+        "hsla(120, 60%, 70%, 0.3)",
+        "hsla(120 60% 70% / 30%)",
+    ] {
+        assert!(is_valid_color(color), "{} should be valid", color);
+    }
+}
+
+#[test]
+fn test_invalid_colors() {
+    for color in [
+        "",
+        "notacolor",
+        "#ff",
+        "#12345",
+        "#ggg",
+        "ffffff",
+        "rgb(300,,)",
+        "rgb(300, 0, 0)",
+        "rgb(0, 0)",
+        "rgba(0, 0, 0, 2)",
+        "rgb(0 0 0 0)",
+        "hsl(0, 100, 50%)",
+        "hsl(0, 120%, 50%)",
+        "cmyk(0, 0, 0, 0)",
+        "rgb(0, 0, 0",
+    ] {
+        assert!(!is_valid_color(color), "{} should be invalid", color);
+    }
+}
@@ -0,0 +1,79 @@
+use crate::raw_js::RawJs;
+use serde_json::{Map, Value};
+
+/// Options for [chartjs-plugin-datalabels](https://chartjs-plugin-datalabels.netlify.app/),
+/// which QuickChart bundles. Apply them with
+/// [`data_labels()`](crate::QuickchartClient::data_labels).
+///
+/// # Example
+///
+/// ```
+/// use quickchart_rs::{DataLabels, QuickchartClient, RawJs};
+///
+/// let client = QuickchartClient::new()
+///     .chart(r#"{"type":"bar","data":{"labels":["A","B"],"datasets":[{"data":[1,2]}]}}"#.to_string())
+///     .data_labels(
+///         DataLabels::new()
+///             .anchor("end".to_string())
+///             .align("top".to_string())
+///             .formatter(RawJs::new("(value) => value + '%'".to_string())),
+///     );
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[must_use]
+pub struct DataLabels {
+    align: Option<String>,
+    anchor: Option<String>,
+    color: Option<String>,
+    formatter: Option<RawJs>,
+}
+
+impl DataLabels {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Position of the label relative to its anchor point: "center", "start", "end", "top",
+    /// "bottom", "left" or "right".
+    pub fn align(mut self, align: String) -> Self {
+        self.align = Some(align);
+        self
+    }
+
+    /// Anchor point on the data element: "center", "start" or "end".
+    pub fn anchor(mut self, anchor: String) -> Self {
+        self.anchor = Some(anchor);
+        self
+    }
+
+    pub fn color(mut self, color: String) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// JavaScript function that formats each label, e.g. `(value, context) => value + '%'`.
+    /// Setting a formatter makes the chart config JS-notation.
+    pub fn formatter(mut self, formatter: RawJs) -> Self {
+        self.formatter = Some(formatter);
+        self
+    }
+
+    /// The `options.plugins.datalabels` entries. `display` is always set so the plugin renders.
+    pub(crate) fn to_value(&self) -> Value {
+        let mut labels = Map::new();
+        labels.insert("display".to_string(), Value::Bool(true));
+        if let Some(ref align) = self.align {
+            labels.insert("align".to_string(), Value::String(align.clone()));
+        }
+        if let Some(ref anchor) = self.anchor {
+            labels.insert("anchor".to_string(), Value::String(anchor.clone()));
+        }
+        if let Some(ref color) = self.color {
+            labels.insert("color".to_string(), Value::String(color.clone()));
+        }
+        if let Some(ref formatter) = self.formatter {
+            labels.insert("formatter".to_string(), formatter.to_value());
+        }
+        Value::Object(labels)
+    }
+}
@@ -0,0 +1,76 @@
+use crate::chart::ChartType;
+use crate::{QCError, QuickchartClient};
+use polars::prelude::{DataFrame, DataType};
+
+#[cfg(test)]
+#[path = "dataframe_test.rs"]
+mod tests;
+
+impl QuickchartClient {
+    /// Build a chart from Polars DataFrame columns: `x_col` supplies the labels (converted to
+    /// strings) and each of `y_cols` becomes a dataset labelled with its column name. Requires the
+    /// `polars` feature.
+    ///
+    /// Null labels become empty strings and null values become `null` points, which Chart.js
+    /// draws as gaps. Returns [`QCError::InvalidData`] if a column is missing or a y column isn't
+    /// numeric.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use polars::prelude::*;
+    /// use quickchart_rs::{ChartType, QuickchartClient};
+    ///
+    /// let df = df!("month" => ["Jan", "Feb"], "sales" => [50.0, 60.0]).unwrap();
+    /// let client = QuickchartClient::from_dataframe(&df, "month", &["sales"], ChartType::Bar)
+    ///     .unwrap()
+    ///     .width(800);
+    /// ```
+    pub fn from_dataframe(
+        df: &DataFrame,
+        x_col: &str,
+        y_cols: &[&str],
+        chart_type: ChartType,
+    ) -> Result<Self, QCError> {
+        let labels: Vec<String> = df
+            .column(x_col)
+            .and_then(|column| column.cast(&DataType::String))
+            .map_err(|e| QCError::InvalidData(e.to_string()))?
+            .str()
+            .map_err(|e| QCError::InvalidData(e.to_string()))?
+            .iter()
+            .map(|label| label.unwrap_or_default().to_string())
+            .collect();
+
+        let datasets = y_cols
+            .iter()
+            .map(|&name| {
+                let column = df
+                    .column(name)
+                    .map_err(|e| QCError::InvalidData(e.to_string()))?;
+                if !column.dtype().is_primitive_numeric() {
+                    return Err(QCError::InvalidData(format!(
+                        "column {:?} is not numeric (found {})",
+                        name,
+                        column.dtype()
+                    )));
+                }
+
+                let values: Vec<Option<f64>> = column
+                    .cast(&DataType::Float64)
+                    .map_err(|e| QCError::InvalidData(e.to_string()))?
+                    .f64()
+                    .map_err(|e| QCError::InvalidData(e.to_string()))?
+                    .iter()
+                    .collect();
+                Ok(serde_json::json!({ "label": name, "data": values }))
+            })
+            .collect::<Result<Vec<_>, QCError>>()?;
+
+        let chart = serde_json::json!({
+            "type": chart_type.as_str(),
+            "data": { "labels": labels, "datasets": datasets },
+        });
+        Ok(QuickchartClient::new().chart(chart.to_string()))
+    }
+}
@@ -0,0 +1,41 @@
+use super::*;
+use polars::prelude::*;
+
+#[test]
+fn test_from_dataframe() {
+    let df = df!(
+        "month" => ["Jan", "Feb", "Mar"],
+        "sales" => [Some(50i64), None, Some(70)],
+        "returns" => [1.5f64, 2.0, 0.5],
+    )
+    .unwrap();
+
+    let client = QuickchartClient::from_dataframe(&df, "month", &["sales", "returns"], ChartType::Line)
+        .unwrap()
+        .keep_animation();
+    let body = client.build_json_body();
+    let chart = &body["chart"];
+
+    assert_eq!(chart["type"], "line");
+    assert_eq!(chart["data"]["labels"], serde_json::json!(["Jan", "Feb", "Mar"]));
+    assert_eq!(chart["data"]["datasets"][0]["label"], "sales");
+    assert_eq!(chart["data"]["datasets"][0]["data"], serde_json::json!([50.0, null, 70.0]));
+    assert_eq!(chart["data"]["datasets"][1]["label"], "returns");
+    assert_eq!(chart["data"]["datasets"][1]["data"], serde_json::json!([1.5, 2.0, 0.5]));
+}
+
+#[test]
+fn test_from_dataframe_rejects_non_numeric_column() {
+    let df = df!("month" => ["Jan"], "note" => ["high"]).unwrap();
+
+    let result = QuickchartClient::from_dataframe(&df, "month", &["note"], ChartType::Bar);
+    assert!(matches!(result, Err(QCError::InvalidData(ref message)) if message.contains("note")));
+}
+
+#[test]
+fn test_from_dataframe_missing_column() {
+    let df = df!("month" => ["Jan"]).unwrap();
+
+    let result = QuickchartClient::from_dataframe(&df, "month", &["sales"], ChartType::Bar);
+    assert!(matches!(result, Err(QCError::InvalidData(_))));
+}
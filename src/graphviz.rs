@@ -0,0 +1,84 @@
+/// A Graphviz diagram for QuickChart's `/graphviz` endpoint. Render it with
+/// [`graphviz_url()`](crate::QuickchartClient::graphviz_url) or
+/// [`graphviz_post()`](crate::QuickchartClient::graphviz_post), which use the client's host and
+/// HTTP settings but none of its chart settings.
+///
+/// Unset options are left to QuickChart's defaults: the `dot` layout, SVG, sized to fit the
+/// graph.
+///
+/// # Example
+///
+/// ```
+/// use quickchart_rs::{Graphviz, QuickchartClient};
+///
+/// let graph = Graphviz::new("digraph { a -> b }".to_string())
+///     .layout("neato".to_string())
+///     .format("png".to_string());
+/// let url = QuickchartClient::new().graphviz_url(&graph).unwrap();
+///
+/// assert!(url.starts_with("https://quickchart.io/graphviz?graph=digraph"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[must_use]
+pub struct Graphviz {
+    dot: String,
+    layout: Option<String>,
+    format: Option<String>,
+    width: Option<u32>,
+    height: Option<u32>,
+}
+
+impl Graphviz {
+    /// A diagram described by the DOT source `dot`.
+    pub fn new(dot: String) -> Self {
+        Graphviz {
+            dot,
+            layout: None,
+            format: None,
+            width: None,
+            height: None,
+        }
+    }
+
+    /// Graphviz layout engine: "dot", "neato", "fdp", "circo", "osage" or "twopi".
+    pub fn layout(mut self, layout: String) -> Self {
+        self.layout = Some(layout);
+        self
+    }
+
+    /// Output format: "svg" or "png".
+    pub fn format(mut self, format: String) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    /// Width of the image in pixels.
+    pub fn width(mut self, width: u32) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    /// Height of the image in pixels.
+    pub fn height(mut self, height: u32) -> Self {
+        self.height = Some(height);
+        self
+    }
+
+    /// The `/graphviz` query parameters, in a stable order.
+    pub(crate) fn query_pairs(&self) -> Vec<(&'static str, String)> {
+        let mut pairs = vec![("graph", self.dot.clone())];
+        if let Some(ref layout) = self.layout {
+            pairs.push(("layout", layout.clone()));
+        }
+        if let Some(ref format) = self.format {
+            pairs.push(("format", format.clone()));
+        }
+        if let Some(width) = self.width {
+            pairs.push(("width", width.to_string()));
+        }
+        if let Some(height) = self.height {
+            pairs.push(("height", height.to_string()));
+        }
+        pairs
+    }
+}
@@ -0,0 +1,50 @@
+use std::fmt;
+
+/// An output format QuickChart can render, for
+/// [`format_typed()`](crate::QuickchartClient::format_typed).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Png,
+    Svg,
+    WebP,
+    Pdf,
+}
+
+impl ImageFormat {
+    /// The `f` / `format` value QuickChart expects.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ImageFormat::Png => "png",
+            ImageFormat::Svg => "svg",
+            ImageFormat::WebP => "webp",
+            ImageFormat::Pdf => "pdf",
+        }
+    }
+
+    /// The MIME type of images in this format, e.g. for a `Content-Type` header or a data URI.
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            ImageFormat::Png => "image/png",
+            ImageFormat::Svg => "image/svg+xml",
+            ImageFormat::WebP => "image/webp",
+            ImageFormat::Pdf => "application/pdf",
+        }
+    }
+
+    /// The format a file extension (without the dot) names, ignoring case, e.g. `"SVG"`.
+    pub fn from_extension(extension: &str) -> Option<ImageFormat> {
+        match extension.to_ascii_lowercase().as_str() {
+            "png" => Some(ImageFormat::Png),
+            "svg" => Some(ImageFormat::Svg),
+            "webp" => Some(ImageFormat::WebP),
+            "pdf" => Some(ImageFormat::Pdf),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ImageFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
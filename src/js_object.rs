@@ -0,0 +1,296 @@
+//! Conversion of JavaScript object notation chart configs to JSON.
+//!
+//! Supports the literal subset Chart.js configs are usually written in: unquoted or
+//! single-quoted keys, single- or double-quoted strings, trailing commas, comments, and
+//! `undefined` (converted to `null`). Anything that needs evaluating, such as functions or
+//! expressions, is rejected.
+
+use serde_json::{Map, Number, Value};
+
+#[cfg(test)]
+#[path = "js_object_test.rs"]
+mod tests;
+
+/// Parse a JS object literal into a JSON value, returning a description of the first problem
+/// found on failure.
+pub(crate) fn parse(input: &str) -> Result<Value, String> {
+    let mut parser = Parser {
+        chars: input.chars().collect(),
+        pos: 0,
+    };
+    let value = parser.parse_value()?;
+    parser.skip_whitespace_and_comments()?;
+    if parser.pos < parser.chars.len() {
+        return Err(parser.error("unexpected trailing characters"));
+    }
+    Ok(value)
+}
+
+/// Check that a JS-notation config that may contain code `parse()` can't handle, such as
+/// functions, is at least structurally sound: it starts with `{`, every string and comment is
+/// closed, and brackets, braces and parentheses are balanced and properly nested, with nothing
+/// after the outermost object. Expressions themselves aren't checked.
+pub(crate) fn check_structure(input: &str) -> Result<(), String> {
+    let mut parser = Parser {
+        chars: input.chars().collect(),
+        pos: 0,
+    };
+    parser.skip_whitespace_and_comments()?;
+    if parser.peek() != Some('{') {
+        return Err(parser.error("expected the config to start with '{'"));
+    }
+
+    let mut open: Vec<(char, usize)> = Vec::new();
+    let mut closed = false;
+    loop {
+        parser.skip_whitespace_and_comments()?;
+        let Some(ch) = parser.peek() else { break };
+        if closed {
+            return Err(parser.error("unexpected characters after the config object"));
+        }
+        match ch {
+            '"' | '\'' | '`' => {
+                parser.parse_string(ch)?;
+                continue;
+            }
+            '{' | '[' | '(' => open.push((ch, parser.pos)),
+            '}' | ']' | ')' => {
+                let expected = match open.pop() {
+                    Some(('{', _)) => '}',
+                    Some(('[', _)) => ']',
+                    Some(_) => ')',
+                    None => return Err(parser.error(&format!("unmatched '{}'", ch))),
+                };
+                if ch != expected {
+                    let message = format!("expected '{}' but found '{}'", expected, ch);
+                    return Err(parser.error(&message));
+                }
+                closed = open.is_empty();
+            }
+            _ => {}
+        }
+        parser.pos += 1;
+    }
+
+    match open.last() {
+        Some(&(ch, pos)) => Err(format!("unclosed '{}' at character {}", ch, pos)),
+        None => Ok(()),
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn error(&self, message: &str) -> String {
+        format!("{} at character {}", message, self.pos)
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        if self.peek() == Some(expected) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", expected)))
+        }
+    }
+
+    fn skip_whitespace_and_comments(&mut self) -> Result<(), String> {
+        loop {
+            match (self.peek(), self.chars.get(self.pos + 1).copied()) {
+                (Some(ch), _) if ch.is_whitespace() => self.pos += 1,
+                (Some('/'), Some('/')) => {
+                    while self.peek().is_some_and(|ch| ch != '\n') {
+                        self.pos += 1;
+                    }
+                }
+                (Some('/'), Some('*')) => {
+                    self.pos += 2;
+                    loop {
+                        match self.peek() {
+                            None => return Err(self.error("unterminated comment")),
+                            Some('*') if self.chars.get(self.pos + 1) == Some(&'/') => {
+                                self.pos += 2;
+                                break;
+                            }
+                            Some(_) => self.pos += 1,
+                        }
+                    }
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<Value, String> {
+        self.skip_whitespace_and_comments()?;
+        match self.peek() {
+            Some('{') => self.parse_object(),
+            Some('[') => self.parse_array(),
+            Some(quote @ ('"' | '\'')) => self.parse_string(quote).map(Value::String),
+            Some(ch) if ch == '-' || ch == '+' || ch == '.' || ch.is_ascii_digit() => {
+                self.parse_number()
+            }
+            Some(ch) if is_identifier_start(ch) => {
+                let start = self.pos;
+                let identifier = self.parse_identifier();
+                match identifier.as_str() {
+                    "true" => Ok(Value::Bool(true)),
+                    "false" => Ok(Value::Bool(false)),
+                    "null" | "undefined" => Ok(Value::Null),
+                    _ => {
+                        self.pos = start;
+                        Err(self.error(&format!(
+                            "unsupported expression starting with '{}'",
+                            identifier
+                        )))
+                    }
+                }
+            }
+            Some(_) => Err(self.error("unexpected character")),
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<Value, String> {
+        self.expect('{')?;
+        let mut map = Map::new();
+        loop {
+            self.skip_whitespace_and_comments()?;
+            if self.peek() == Some('}') {
+                self.pos += 1;
+                return Ok(Value::Object(map));
+            }
+
+            let key = match self.peek() {
+                Some(quote @ ('"' | '\'')) => self.parse_string(quote)?,
+                Some(ch) if is_identifier_start(ch) || ch.is_ascii_digit() => {
+                    self.parse_identifier()
+                }
+                _ => return Err(self.error("expected a property name")),
+            };
+            self.skip_whitespace_and_comments()?;
+            self.expect(':')?;
+            let value = self.parse_value()?;
+            map.insert(key, value);
+
+            self.skip_whitespace_and_comments()?;
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some('}') => {}
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+    }
+
+    fn parse_array(&mut self) -> Result<Value, String> {
+        self.expect('[')?;
+        let mut items = Vec::new();
+        loop {
+            self.skip_whitespace_and_comments()?;
+            if self.peek() == Some(']') {
+                self.pos += 1;
+                return Ok(Value::Array(items));
+            }
+
+            items.push(self.parse_value()?);
+
+            self.skip_whitespace_and_comments()?;
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some(']') => {}
+                _ => return Err(self.error("expected ',' or ']'")),
+            }
+        }
+    }
+
+    fn parse_string(&mut self, quote: char) -> Result<String, String> {
+        self.expect(quote)?;
+        let mut string = String::new();
+        loop {
+            match self.peek() {
+                None => return Err(self.error("unterminated string")),
+                Some(ch) if ch == quote => {
+                    self.pos += 1;
+                    return Ok(string);
+                }
+                Some('\\') => {
+                    self.pos += 1;
+                    let escaped = self.peek().ok_or_else(|| self.error("unterminated string"))?;
+                    self.pos += 1;
+                    match escaped {
+                        'n' => string.push('\n'),
+                        't' => string.push('\t'),
+                        'r' => string.push('\r'),
+                        'b' => string.push('\u{8}'),
+                        'f' => string.push('\u{c}'),
+                        'u' => string.push(self.parse_unicode_escape()?),
+                        '\n' => {}
+                        other => string.push(other),
+                    }
+                }
+                Some(ch) => {
+                    string.push(ch);
+                    self.pos += 1;
+                }
+            }
+        }
+    }
+
+    fn parse_unicode_escape(&mut self) -> Result<char, String> {
+        let end = self.pos + 4;
+        let digits: String = self.chars.get(self.pos..end).unwrap_or_default().iter().collect();
+        let code = u32::from_str_radix(&digits, 16)
+            .map_err(|_| self.error("invalid unicode escape"))?;
+        self.pos = end;
+        char::from_u32(code).ok_or_else(|| self.error("invalid unicode escape"))
+    }
+
+    fn parse_number(&mut self) -> Result<Value, String> {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '+' | '-' | '.'))
+        {
+            self.pos += 1;
+        }
+        let literal: String = self.chars[start..self.pos].iter().collect();
+        let normalized = literal.trim_start_matches('+');
+
+        if let Ok(integer) = normalized.parse::<i64>() {
+            return Ok(Value::Number(integer.into()));
+        }
+        normalized
+            .parse::<f64>()
+            .ok()
+            .filter(|float| float.is_finite())
+            .and_then(Number::from_f64)
+            .map(Value::Number)
+            .ok_or_else(|| {
+                self.pos = start;
+                self.error(&format!("invalid number '{}'", literal))
+            })
+    }
+
+    fn parse_identifier(&mut self) -> String {
+        let start = self.pos;
+        while self.peek().is_some_and(is_identifier_char) {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+}
+
+fn is_identifier_start(ch: char) -> bool {
+    ch.is_alphabetic() || ch == '_' || ch == '$'
+}
+
+fn is_identifier_char(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '_' || ch == '$'
+}
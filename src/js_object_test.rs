@@ -0,0 +1,67 @@
+use super::*;
+
+#[test]
+fn test_parse_chart_js_notation() {
+    let config = r#"{
+        type: 'bar', // inline comment
+        data: {
+            labels: ['January', "Feb \"2\"", 'It\'s'],
+            datasets: [{ label: 'Sales', data: [50, -1.5, .5, 1e3,], hidden: undefined }],
+        },
+        /* block
+           comment */
+        options: { $custom_key: true, 'quoted-key': null },
+    }"#;
+
+    assert_eq!(
+        parse(config).unwrap(),
+        serde_json::json!({
+            "type": "bar",
+            "data": {
+                "labels": ["January", "Feb \"2\"", "It's"],
+                "datasets": [{ "label": "Sales", "data": [50, -1.5, 0.5, 1000.0], "hidden": null }],
+            },
+            "options": { "$custom_key": true, "quoted-key": null },
+        })
+    );
+}
+
+#[test]
+fn test_parse_json() {
+    let config = r#"{"type":"line","data":{"datasets":[{"data":[1,2,3]}]}}"#;
+    assert_eq!(parse(config).unwrap(), serde_json::from_str::<Value>(config).unwrap());
+}
+
+#[test]
+fn test_parse_rejects_functions_and_expressions() {
+    let error = parse("{options: {formatter: function(v) { return v; }}}").unwrap_err();
+    assert!(error.contains("function"));
+
+    assert!(parse("{data: [Math.PI]}").is_err());
+    assert!(parse("{type: 'bar'").is_err());
+    assert!(parse("{type: 'bar'} extra").is_err());
+}
+
+#[test]
+fn test_check_structure() {
+    let config = r#"
+        // labels are formatted in the browser
+        {
+            type: 'bar',
+            options: { plugins: { datalabels: { formatter: (v) => `${v} }` + ']' } } },
+            /* "unbalanced" ( in a comment */
+        }
+    "#;
+    assert!(check_structure(config).is_ok());
+
+    for (config, message) in [
+        ("type: 'bar'", "expected the config to start with '{' at character 0"),
+        ("{type: 'bar'", "unclosed '{' at character 0"),
+        ("{data: [1, 2}", "expected ']' but found '}' at character 12"),
+        ("{type: 'bar}", "unterminated string at character 12"),
+        ("{} {}", "unexpected characters after the config object at character 3"),
+        ("{/* open", "unterminated comment at character 8"),
+    ] {
+        assert_eq!(check_structure(config), Err(message.to_string()), "{}", config);
+    }
+}
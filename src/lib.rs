@@ -10,5 +10,44 @@
 //! - Create short URLs for sharing charts
 //! - Builder pattern API for easy configuration
 
+mod chart;
+mod chart_options;
+mod chart_template;
+mod chart_version;
+mod color;
+mod data_labels;
+#[cfg(feature = "polars")]
+mod dataframe;
+mod graphviz;
+mod image_format;
+mod js_object;
+mod ndjson;
+mod number_format;
+mod qr_code;
 mod quickchart_client;
-pub use quickchart_client::{QuickchartClient, QCError};
\ No newline at end of file
+mod raw_js;
+mod session;
+#[cfg(feature = "image")]
+mod small_multiples;
+#[cfg(test)]
+mod test_util;
+#[cfg(feature = "chrono")]
+mod time_series;
+#[cfg(feature = "zip")]
+mod zip_archive;
+pub use chart::{Chart, ChartData, ChartType, Dataset, PointStyle};
+pub use chart_template::ChartTemplate;
+pub use chart_version::ChartVersion;
+pub use data_labels::DataLabels;
+pub use graphviz::Graphviz;
+pub use image_format::ImageFormat;
+pub use number_format::NumberFormat;
+pub use qr_code::QrCode;
+pub use quickchart_client::{
+    QuickchartClient, QCError, RenderSummary, ShortUrlResponse, SpaceEncoding, UrlEscape,
+    UrlReport, BASE_URL_ENV_VAR, DEFAULT_URL_LENGTH_LIMIT,
+};
+pub use raw_js::RawJs;
+pub use session::QuickchartSession;
+#[cfg(feature = "zip")]
+pub use zip_archive::OnChartError;
\ No newline at end of file
@@ -0,0 +1,67 @@
+use crate::chart::ChartType;
+use crate::{QCError, QuickchartClient};
+use serde_json::Value;
+
+#[cfg(test)]
+#[path = "ndjson_test.rs"]
+mod tests;
+
+impl QuickchartClient {
+    /// Build a single-series chart from JSON Lines (NDJSON) text with one
+    /// `{"label": ..., "value": ...}` object per line.
+    ///
+    /// Lines are parsed one at a time, so only the labels and values are held in memory, not a
+    /// JSON value for the whole input. Blank lines are skipped. Labels may be strings or numbers,
+    /// and a `null` value becomes a gap. Any other record returns [`QCError::InvalidData`] naming
+    /// the 1-based line number.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use quickchart_rs::{ChartType, QuickchartClient};
+    ///
+    /// let ndjson = "{\"label\":\"Jan\",\"value\":50}\n{\"label\":\"Feb\",\"value\":60}\n";
+    /// let client = QuickchartClient::from_ndjson(ndjson, ChartType::Line).unwrap();
+    /// ```
+    pub fn from_ndjson(ndjson: &str, chart_type: ChartType) -> Result<Self, QCError> {
+        let mut labels = Vec::new();
+        let mut values = Vec::new();
+
+        for (i, line) in ndjson.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let (label, value) = parse_record(line)
+                .map_err(|message| QCError::InvalidData(format!("line {}: {}", i + 1, message)))?;
+            labels.push(label);
+            values.push(value);
+        }
+
+        let chart = serde_json::json!({
+            "type": chart_type.as_str(),
+            "data": { "labels": labels, "datasets": [{ "data": values }] },
+        });
+        Ok(QuickchartClient::new().chart(chart.to_string()))
+    }
+}
+
+fn parse_record(line: &str) -> Result<(String, Option<f64>), String> {
+    let record: Value = serde_json::from_str(line).map_err(|e| e.to_string())?;
+    let Value::Object(mut record) = record else {
+        return Err("expected a JSON object".to_string());
+    };
+
+    let label = match record.remove("label") {
+        Some(Value::String(label)) => label,
+        Some(Value::Number(label)) => label.to_string(),
+        Some(_) => return Err("\"label\" must be a string or number".to_string()),
+        None => return Err("missing \"label\" field".to_string()),
+    };
+    let value = match record.remove("value") {
+        Some(Value::Number(value)) => value.as_f64(),
+        Some(Value::Null) => None,
+        Some(_) => return Err("\"value\" must be a number or null".to_string()),
+        None => return Err("missing \"value\" field".to_string()),
+    };
+    Ok((label, value))
+}
@@ -0,0 +1,31 @@
+use super::*;
+
+#[test]
+fn test_from_ndjson() {
+    let ndjson = "{\"label\":\"Jan\",\"value\":50}\n\n{\"label\":2024,\"value\":null}\r\n   \n\
+                  {\"value\":1.5,\"label\":\"Mar\"}";
+
+    let client = QuickchartClient::from_ndjson(ndjson, ChartType::Bar).unwrap();
+    let body = client.build_json_body();
+    let chart = &body["chart"];
+
+    assert_eq!(chart["type"], "bar");
+    assert_eq!(chart["data"]["labels"], serde_json::json!(["Jan", "2024", "Mar"]));
+    assert_eq!(chart["data"]["datasets"][0]["data"], serde_json::json!([50.0, null, 1.5]));
+}
+
+#[test]
+fn test_from_ndjson_reports_line_number() {
+    let ndjson = "{\"label\":\"Jan\",\"value\":50}\n\n{\"label\":\"Feb\",\"value\":\n";
+    let result = QuickchartClient::from_ndjson(ndjson, ChartType::Line);
+    assert!(matches!(result, Err(QCError::InvalidData(ref message)) if message.starts_with("line 3:")));
+
+    let result = QuickchartClient::from_ndjson("{\"label\":\"Jan\"}", ChartType::Line);
+    assert!(matches!(
+        result,
+        Err(QCError::InvalidData(ref message)) if message == "line 1: missing \"value\" field"
+    ));
+
+    let result = QuickchartClient::from_ndjson("[1, 2]", ChartType::Line);
+    assert!(matches!(result, Err(QCError::InvalidData(ref message)) if message.contains("object")));
+}
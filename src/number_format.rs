@@ -0,0 +1,93 @@
+use crate::raw_js::RawJs;
+use serde_json::{Map, Value};
+
+#[cfg(test)]
+#[path = "number_format_test.rs"]
+mod tests;
+
+/// Locale-aware number formatting for axis ticks and data labels, rendered by QuickChart with
+/// JavaScript's `Intl.NumberFormat`. Apply it with
+/// [`number_format()`](crate::QuickchartClient::number_format).
+///
+/// # Example
+///
+/// ```
+/// use quickchart_rs::{NumberFormat, QuickchartClient};
+///
+/// let client = QuickchartClient::new()
+///     .chart(r#"{"type":"bar","data":{"labels":["Q1"],"datasets":[{"data":[12500]}]}}"#.to_string())
+///     .number_format(NumberFormat::currency("EUR".to_string()).locale("de-DE".to_string()));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[must_use]
+pub struct NumberFormat {
+    style: &'static str,
+    currency: Option<String>,
+    locale: Option<String>,
+    fraction_digits: Option<u8>,
+}
+
+impl NumberFormat {
+    /// Plain numbers with the locale's grouping, e.g. `12,500.5`.
+    pub fn decimal() -> Self {
+        Self::with_style("decimal")
+    }
+
+    /// Amounts in the given ISO 4217 currency, e.g. `"USD"` formats `12500` as `$12,500.00`.
+    pub fn currency(code: String) -> Self {
+        NumberFormat {
+            currency: Some(code),
+            ..Self::with_style("currency")
+        }
+    }
+
+    /// Fractions shown as percentages: `0.25` formats as `25%`.
+    pub fn percent() -> Self {
+        Self::with_style("percent")
+    }
+
+    fn with_style(style: &'static str) -> Self {
+        NumberFormat {
+            style,
+            currency: None,
+            locale: None,
+            fraction_digits: None,
+        }
+    }
+
+    /// BCP 47 locale tag such as `"en-US"` or `"de-DE"`. Defaults to the locale of the
+    /// QuickChart server.
+    pub fn locale(mut self, locale: String) -> Self {
+        self.locale = Some(locale);
+        self
+    }
+
+    /// Always show exactly this many fraction digits. Defaults to the style's usual precision.
+    pub fn fraction_digits(mut self, digits: u8) -> Self {
+        self.fraction_digits = Some(digits);
+        self
+    }
+
+    /// The formatting callback, taking the number to format as its first argument.
+    pub(crate) fn to_raw_js(&self) -> RawJs {
+        let mut options = Map::new();
+        options.insert("style".to_string(), Value::String(self.style.to_string()));
+        if let Some(ref currency) = self.currency {
+            options.insert("currency".to_string(), Value::String(currency.clone()));
+        }
+        if let Some(digits) = self.fraction_digits {
+            options.insert("minimumFractionDigits".to_string(), digits.into());
+            options.insert("maximumFractionDigits".to_string(), digits.into());
+        }
+        let locale = match self.locale {
+            Some(ref locale) => Value::String(locale.clone()).to_string(),
+            None => "undefined".to_string(),
+        };
+
+        RawJs::new(format!(
+            "function(value) {{ return new Intl.NumberFormat({}, {}).format(value); }}",
+            locale,
+            Value::Object(options)
+        ))
+    }
+}
@@ -0,0 +1,24 @@
+use super::*;
+
+#[test]
+fn test_currency_callback() {
+    let format = NumberFormat::currency("EUR".to_string())
+        .locale("de-DE".to_string())
+        .fraction_digits(2);
+
+    assert_eq!(
+        format.to_raw_js().as_str(),
+        "function(value) { return new Intl.NumberFormat(\"de-DE\", \
+         {\"style\":\"currency\",\"currency\":\"EUR\",\"minimumFractionDigits\":2,\"maximumFractionDigits\":2})\
+         .format(value); }"
+    );
+}
+
+#[test]
+fn test_default_locale_callback() {
+    assert_eq!(
+        NumberFormat::percent().to_raw_js().as_str(),
+        "function(value) { return new Intl.NumberFormat(undefined, {\"style\":\"percent\"}).format(value); }"
+    );
+    assert!(NumberFormat::decimal().to_raw_js().as_str().contains("{\"style\":\"decimal\"}"));
+}
@@ -0,0 +1,95 @@
+/// A QR code for QuickChart's `/qr` endpoint. Render it with
+/// [`qr_url()`](crate::QuickchartClient::qr_url) or
+/// [`qr_post()`](crate::QuickchartClient::qr_post), which use the client's host and HTTP
+/// settings but none of its chart settings.
+///
+/// Unset options are left to QuickChart's defaults: 150px, a 4-module margin, black on white,
+/// PNG.
+///
+/// # Example
+///
+/// ```
+/// use quickchart_rs::{QrCode, QuickchartClient};
+///
+/// let qr = QrCode::new("https://example.com".to_string())
+///     .size(300)
+///     .dark_color("1f4e79".to_string());
+/// let url = QuickchartClient::new().qr_url(&qr).unwrap();
+///
+/// assert!(url.starts_with("https://quickchart.io/qr?text=https%3A%2F%2Fexample.com&size=300"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[must_use]
+pub struct QrCode {
+    text: String,
+    size: Option<u32>,
+    margin: Option<u32>,
+    dark_color: Option<String>,
+    light_color: Option<String>,
+    format: Option<String>,
+}
+
+impl QrCode {
+    /// A QR code encoding `text`.
+    pub fn new(text: String) -> Self {
+        QrCode {
+            text,
+            size: None,
+            margin: None,
+            dark_color: None,
+            light_color: None,
+            format: None,
+        }
+    }
+
+    /// Width and height of the image in pixels.
+    pub fn size(mut self, size: u32) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    /// Width of the blank border around the code, in modules (QR code "pixels").
+    pub fn margin(mut self, margin: u32) -> Self {
+        self.margin = Some(margin);
+        self
+    }
+
+    /// Color of the dark modules as a hex code without the `#`, e.g. `"1f4e79"`.
+    pub fn dark_color(mut self, color: String) -> Self {
+        self.dark_color = Some(color);
+        self
+    }
+
+    /// Background color as a hex code without the `#`, e.g. `"ffffff"`.
+    pub fn light_color(mut self, color: String) -> Self {
+        self.light_color = Some(color);
+        self
+    }
+
+    /// Output format: "png" or "svg".
+    pub fn format(mut self, format: String) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    /// The `/qr` query parameters, in a stable order.
+    pub(crate) fn query_pairs(&self) -> Vec<(&'static str, String)> {
+        let mut pairs = vec![("text", self.text.clone())];
+        if let Some(size) = self.size {
+            pairs.push(("size", size.to_string()));
+        }
+        if let Some(margin) = self.margin {
+            pairs.push(("margin", margin.to_string()));
+        }
+        if let Some(ref dark) = self.dark_color {
+            pairs.push(("dark", dark.clone()));
+        }
+        if let Some(ref light) = self.light_color {
+            pairs.push(("light", light.clone()));
+        }
+        if let Some(ref format) = self.format {
+            pairs.push(("format", format.clone()));
+        }
+        pairs
+    }
+}
@@ -1,15 +1,97 @@
-use reqwest::{Client, Url};
+use crate::chart_options::{merge_at, ChartOptions};
+use crate::chart_template::ChartTemplate;
+use crate::chart_version::ChartVersion;
+use crate::color;
+use crate::data_labels::DataLabels;
+use crate::graphviz::Graphviz;
+use crate::image_format::ImageFormat;
+use crate::js_object;
+use crate::number_format::NumberFormat;
+use crate::qr_code::QrCode;
+use crate::raw_js;
+use base64::Engine;
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use reqwest::{Certificate, Client, Url};
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::fmt;
+use std::future::Future;
+use std::io::Write;
 use std::path::Path;
+use std::time::Duration;
 use thiserror::Error;
 
+#[cfg(feature = "blocking")]
+#[path = "blocking.rs"]
+mod blocking;
 #[cfg(test)]
 #[path = "quickchart_client_test.rs"]
 mod tests;
 
 const BASE_URL: &str = "https://quickchart.io";
+
+/// Environment variable read by [`QuickchartClient::new_from_env()`] to override [`BASE_URL`].
+pub const BASE_URL_ENV_VAR: &str = "QUICKCHART_BASE_URL";
 const USER_AGENT: &str = concat!("quickchart-rs/", env!("CARGO_PKG_VERSION"));
-const CHART_ENDPOINT: &str = "/chart";
-const CREATE_ENDPOINT: &str = "/chart/create";
+/// Endpoints, relative to the base URL so that a base URL with a path prefix keeps it.
+const CHART_ENDPOINT: &str = "chart";
+const CREATE_ENDPOINT: &str = "chart/create";
+const QR_ENDPOINT: &str = "qr";
+const GRAPHVIZ_ENDPOINT: &str = "graphviz";
+const TEMPLATE_ENDPOINT: &str = "chart/render";
+/// Chart types Chart.js and the plugins bundled with QuickChart can render.
+const KNOWN_CHART_TYPES: &[&str] = &[
+    "bar",
+    "horizontalBar",
+    "line",
+    "pie",
+    "doughnut",
+    "radar",
+    "polarArea",
+    "scatter",
+    "bubble",
+    "boxplot",
+    "horizontalBoxplot",
+    "violin",
+    "horizontalViolin",
+    "candlestick",
+    "ohlc",
+    "gauge",
+    "radialGauge",
+    "matrix",
+    "outlabeledPie",
+    "progressBar",
+    "sankey",
+    "sparkline",
+    "treemap",
+    "wordCloud",
+];
+/// Chart.js plugins that are part of Chart.js itself, and so don't count towards
+/// [`QuickchartClient::complexity_score`].
+const BUILT_IN_PLUGINS: &[&str] = &["legend", "title", "subtitle", "tooltip", "filler", "decimation"];
+/// QuickChart's defaults, pinned explicitly by `deterministic()`.
+const DEFAULT_CHART_JS_VERSION: &str = "2.9.4";
+const DEFAULT_WIDTH: usize = 500;
+const DEFAULT_HEIGHT: usize = 300;
+/// How much of an error response body is read for the error message, at most; the rest is
+/// discarded unread.
+const MAX_ERROR_BODY_BYTES: usize = 64 * 1024;
+const DEFAULT_DEVICE_PIXEL_RATIO: f32 = 2.0;
+const CHAT_UNFURL_WIDTH: usize = 800;
+const CHAT_UNFURL_HEIGHT: usize = 420;
+const SPARKLINE_WIDTH: usize = 100;
+const SPARKLINE_HEIGHT: usize = 30;
+const DEFAULT_RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+/// Longest `Retry-After` wait honored before a retry, so one bad header can't stall a request.
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+/// Longest URL, in bytes, considered safe for a GET request. 8 KiB is the default request line
+/// limit of common servers and proxies such as nginx.
+const MAX_GET_URL_LENGTH: usize = 8192;
+/// Conservative URL length, in bytes, for [`is_url_too_long()`](QuickchartClient::is_url_too_long)
+/// when the URL will be used in browsers, emails or chat messages rather than fetched directly.
+pub const DEFAULT_URL_LENGTH_LIMIT: usize = 2000;
+#[cfg(feature = "image")]
+const BLANK_CHECK_SAMPLES_PER_AXIS: u64 = 64;
 
 /// Client for interacting with the QuickChart.io API.
 ///
@@ -30,16 +112,198 @@ const CREATE_ENDPOINT: &str = "/chart/create";
 /// let url = client.get_url().unwrap();
 /// assert!(url.starts_with("https://quickchart.io/chart"));
 /// ```
+///
+/// Builder methods take the client by value and return the updated one, so the type is
+/// `#[must_use]`: a step whose result is dropped does nothing, and the compiler warns about it.
+///
+/// ```compile_fail
+/// #![deny(unused_must_use)]
+/// use quickchart_rs::QuickchartClient;
+///
+/// let client = QuickchartClient::new();
+/// client.clone().width(800); // error: unused `QuickchartClient` that must be used
+/// ```
+///
+/// # Connection reuse
+///
+/// Each [`new()`](QuickchartClient::new) builds its own HTTP client and connection pool, while
+/// clones share the original's. To render many charts over the same connections, configure one
+/// client and clone it per chart, or use a [`QuickchartSession`](crate::QuickchartSession).
+///
+/// # Features
+///
+/// Requests are async by default. Some methods are only available with a cargo feature:
+///
+/// - `blocking`: `post_blocking()`, `get_short_url_blocking()` and `to_file_blocking()`,
+///   synchronous versions of their async counterparts.
+/// - `image`: `post_checked()`, `to_image()` and `small_multiples()`.
+/// - `polars`: `from_dataframe()`.
+/// - `chrono`: `from_time_series()`.
+/// - `zip`: `charts_to_zip()`.
+///
+/// The `tracing` feature emits [`tracing`](https://docs.rs/tracing) spans for `post()`,
+/// `get_short_url()` and `to_file()`, and debug events for each request sent and each URL
+/// built. Without it no tracing code is compiled in.
+///
+/// The default `gzip` and `brotli` features let the client accept compressed responses; see
+/// [`compression()`](QuickchartClient::compression).
+#[derive(Clone)]
+#[must_use = "builder methods return the updated client rather than modifying it in place"]
 pub struct QuickchartClient {
     client: Client,
-    base_url: Url,
+    #[cfg(feature = "blocking")]
+    blocking_client: std::sync::OnceLock<reqwest::blocking::Client>,
+    http_settings: HttpSettings,
+    pub(crate) base_url: Url,
+    fallback_base_urls: Vec<Url>,
+    fallback_image: Option<Vec<u8>>,
+    retries: u32,
+    retry_base_delay: Duration,
+    max_response_bytes: Option<usize>,
     chart: String,
+    chart_json: Option<serde_json::Value>,
     width: Option<usize>,
     height: Option<usize>,
     device_pixel_ratio: Option<f32>,
+    scale: Option<f32>,
+    invalid_aspect_ratio: Option<f32>,
     background_color: Option<String>,
     version: Option<String>,
+    api_key: Option<String>,
+    extra_params: Vec<(String, String)>,
+    headers: Vec<(String, String)>,
     format: Option<String>,
+    default_format: Option<String>,
+    url_escape: UrlEscape,
+    space_encoding: SpaceEncoding,
+    base64_chart: bool,
+    json_only: bool,
+    pub(crate) options: ChartOptions,
+}
+
+/// How the chart config (the `c` parameter) is percent-encoded by
+/// [`get_url()`](QuickchartClient::get_url). Other query parameters are always form-encoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UrlEscape {
+    /// `application/x-www-form-urlencoded`, as browsers encode form data: alphanumerics and
+    /// `*-._` are kept, spaces become `+`, everything else is percent-encoded. The default.
+    #[default]
+    Form,
+    /// Percent-encode every character except ASCII letters and digits, including spaces (`%20`).
+    /// For consumers that mishandle `+` or other characters left bare by form encoding.
+    NonAlphanumeric,
+}
+
+impl UrlEscape {
+    fn encode(self, value: &str) -> String {
+        match self {
+            UrlEscape::Form => url::form_urlencoded::byte_serialize(value.as_bytes()).collect(),
+            UrlEscape::NonAlphanumeric => utf8_percent_encode(value, NON_ALPHANUMERIC).to_string(),
+        }
+    }
+}
+
+/// How spaces are written in the query of [`get_url()`](QuickchartClient::get_url), chosen
+/// with [`space_encoding()`](QuickchartClient::space_encoding).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SpaceEncoding {
+    /// `+`, as in `application/x-www-form-urlencoded`. The default.
+    #[default]
+    Plus,
+    /// `%20`, for consumers such as older email clients that don't decode `+` as a space.
+    Percent,
+}
+
+/// Read-only summary of the request a client would send, returned by
+/// [`describe()`](QuickchartClient::describe).
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderSummary {
+    /// URL of the chart endpoint images are rendered from.
+    pub endpoint: String,
+    pub width: Option<usize>,
+    pub height: Option<usize>,
+    pub device_pixel_ratio: Option<f32>,
+    pub format: Option<String>,
+    pub background_color: Option<String>,
+    /// Length in bytes of the minified chart config.
+    pub config_bytes: usize,
+    /// Whether the config (with builder options merged in) is strict JSON rather than
+    /// JS-notation.
+    pub is_json: bool,
+    /// Length in bytes of the URL [`get_url()`](QuickchartClient::get_url) would produce, or
+    /// `None` if it would fail, e.g. because no chart config is set.
+    pub estimated_url_length: Option<usize>,
+}
+
+/// The body of a `/chart/create` response, returned by
+/// [`get_short_url_response()`](QuickchartClient::get_short_url_response).
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ShortUrlResponse {
+    /// Whether QuickChart reported success. `false` if the response has no `success` field.
+    #[serde(default)]
+    pub success: bool,
+    /// The short URL the chart is rendered from. Built from the response's `id` if QuickChart
+    /// sent one instead of a URL.
+    pub url: String,
+    /// URL of the rendered image, if the response has an `imageUrl` field.
+    #[serde(default, rename = "imageUrl", alias = "image_url")]
+    pub image_url: Option<String>,
+    /// Any other fields in the response.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A chart URL with its size, returned by [`check_url()`](QuickchartClient::check_url).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UrlReport {
+    /// The URL [`get_url()`](QuickchartClient::get_url) produces.
+    pub url: String,
+    pub byte_length: usize,
+    /// Whether the URL is at most 8192 bytes, the default request line limit of common servers
+    /// and proxies. Longer charts should be rendered with [`post()`](QuickchartClient::post) or
+    /// shared with [`get_short_url()`](QuickchartClient::get_short_url) instead.
+    pub within_get_limit: bool,
+}
+
+/// Connection settings the internal HTTP client is built from, kept so it can be rebuilt when
+/// one of them changes.
+#[derive(Clone, Default)]
+struct HttpSettings {
+    /// Client supplied through [`with_client()`](QuickchartClient::with_client), used as-is
+    /// instead of building one.
+    custom_client: Option<Client>,
+    root_certificates: Vec<Certificate>,
+    http1_only: bool,
+    timeout: Option<Duration>,
+    /// Set by `compression(false)`; negated so the derived default leaves compression on.
+    compression_disabled: bool,
+}
+
+impl HttpSettings {
+    fn build_client(&self) -> Client {
+        if let Some(ref client) = self.custom_client {
+            return client.clone();
+        }
+        let mut builder = Client::builder().user_agent(USER_AGENT);
+        for certificate in &self.root_certificates {
+            builder = builder.add_root_certificate(certificate.clone());
+        }
+        if self.http1_only {
+            builder = builder.http1_only();
+        }
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        #[cfg(feature = "gzip")]
+        {
+            builder = builder.gzip(!self.compression_disabled);
+        }
+        #[cfg(feature = "brotli")]
+        {
+            builder = builder.brotli(!self.compression_disabled);
+        }
+        builder.build().expect("Failed to create HTTP client")
+    }
 }
 
 /// Errors that can occur when using the QuickChart client.
@@ -55,6 +319,113 @@ pub enum QCError {
     IoError(#[from] std::io::Error),
     #[error("Missing field in response: {0}")]
     MissingField(String),
+    /// A `/chart/create` response had no short URL but other fields that explain or stand in
+    /// for it, such as an error message or only an image URL. The message describes them.
+    #[error("No short URL in response: {0}")]
+    NoShortUrl(String),
+    /// A parameter of the URL passed to [`from_url()`](QuickchartClient::from_url) has a value
+    /// that can't be read, such as a non-numeric width.
+    #[error("Invalid value {value:?} for URL parameter {name}")]
+    InvalidUrlParam { name: String, value: String },
+    /// The cancellation future passed to
+    /// [`post_with_cancel()`](QuickchartClient::post_with_cancel) or
+    /// [`get_short_url_with_cancel()`](QuickchartClient::get_short_url_with_cancel) completed
+    /// before the request finished.
+    #[error("Request cancelled")]
+    Cancelled,
+    /// [`to_image()`](QuickchartClient::to_image) couldn't decode the response, or the output
+    /// format isn't a raster format it can decode.
+    #[error("Failed to decode image: {0}")]
+    ImageDecode(String),
+    /// A header added with [`header()`](QuickchartClient::header) has a name or value that
+    /// isn't valid in HTTP. Holds the header name.
+    #[error("Invalid HTTP header {0:?}")]
+    InvalidHeader(String),
+    #[error("Rendered image appears to be blank")]
+    BlankImage,
+    #[error("Unrecognized chart type: {0:?}")]
+    UnknownChartType(String),
+    #[error("Invalid chart config: {0}")]
+    InvalidConfig(String),
+    /// [`validate()`](QuickchartClient::validate) found the chart config malformed. Holds a
+    /// description of the first problem found.
+    #[error("Malformed chart config: {0}")]
+    InvalidChart(String),
+    #[error("Invalid chart data: {0}")]
+    InvalidData(String),
+    /// No chart config was set with [`chart()`](QuickchartClient::chart), or it is only
+    /// whitespace.
+    #[error("No chart config set")]
+    EmptyChart,
+    /// QuickChart responded with a 4xx or 5xx status. `message` is the `error` field of its JSON
+    /// error body, or the raw body text if it has none. A 4xx usually means the chart config was
+    /// rejected, a 5xx that the server failed.
+    #[error("QuickChart API error ({status}): {message}")]
+    ApiError { status: u16, message: String },
+    /// A color passed to [`background_color_checked()`](QuickchartClient::background_color_checked)
+    /// isn't in a supported format.
+    #[error("Invalid color: {0:?}")]
+    InvalidColor(String),
+    /// The value set with [`device_pixel_ratio()`](QuickchartClient::device_pixel_ratio) is
+    /// zero, negative, NaN or infinite.
+    #[error("Invalid device pixel ratio {0}: must be a positive, finite number")]
+    InvalidDevicePixelRatio(f32),
+    /// The value set with [`scale()`](QuickchartClient::scale) is zero, negative, NaN or
+    /// infinite.
+    #[error("Invalid scale {0}: must be a positive, finite number")]
+    InvalidScale(f32),
+    /// The ratio given to [`aspect_ratio()`](QuickchartClient::aspect_ratio) is zero, negative,
+    /// NaN or infinite.
+    #[error("Invalid aspect ratio {0}: must be a positive, finite number")]
+    InvalidAspectRatio(f32),
+    /// [`to_file()`](QuickchartClient::to_file) was given a path whose extension names a
+    /// different format than the one set with [`format()`](QuickchartClient::format).
+    #[error("File extension .{extension} doesn't match the requested format {format:?}")]
+    FormatMismatch { extension: String, format: String },
+    /// The response body is larger than the limit set with
+    /// [`max_response_bytes()`](QuickchartClient::max_response_bytes).
+    #[error("Response body exceeds the {limit} byte limit")]
+    ResponseTooLarge { limit: usize },
+}
+
+/// Shows the configured settings. The HTTP client is shown as a placeholder, root certificates
+/// and the fallback image by their count and size.
+impl fmt::Debug for QuickchartClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QuickchartClient")
+            .field("client", &format_args!("reqwest::Client {{ .. }}"))
+            .field("custom_client", &self.http_settings.custom_client.is_some())
+            .field("root_certificates", &self.http_settings.root_certificates.len())
+            .field("http1_only", &self.http_settings.http1_only)
+            .field("compression", &!self.http_settings.compression_disabled)
+            .field("timeout", &self.http_settings.timeout)
+            .field("base_url", &self.base_url.as_str())
+            .field("fallback_base_urls", &self.get_fallback_base_urls())
+            .field("fallback_image_bytes", &self.fallback_image.as_ref().map(Vec::len))
+            .field("retries", &self.retries)
+            .field("retry_base_delay", &self.retry_base_delay)
+            .field("max_response_bytes", &self.max_response_bytes)
+            .field("chart", &self.chart)
+            .field("chart_json", &self.chart_json)
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .field("device_pixel_ratio", &self.device_pixel_ratio)
+            .field("scale", &self.scale)
+            .field("invalid_aspect_ratio", &self.invalid_aspect_ratio)
+            .field("background_color", &self.background_color)
+            .field("version", &self.version)
+            .field("api_key", &self.api_key.as_ref().map(|_| "<redacted>"))
+            .field("headers", &self.headers.iter().map(|(name, _)| name).collect::<Vec<_>>())
+            .field("extra_params", &self.extra_params)
+            .field("format", &self.format)
+            .field("default_format", &self.default_format)
+            .field("url_escape", &self.url_escape)
+            .field("space_encoding", &self.space_encoding)
+            .field("base64_chart", &self.base64_chart)
+            .field("json_only", &self.json_only)
+            .field("options", &self.options)
+            .finish()
+    }
 }
 
 impl Default for QuickchartClient {
@@ -63,121 +434,283 @@ impl Default for QuickchartClient {
     }
 }
 
+/// Parses a chart URL with [`from_url()`](QuickchartClient::from_url).
+impl TryFrom<&str> for QuickchartClient {
+    type Error = QCError;
+
+    fn try_from(url: &str) -> Result<Self, QCError> {
+        Self::from_url(url)
+    }
+}
+
 impl QuickchartClient {
     /// Create a new QuickChart client instance.
     pub fn new() -> Self {
-        let client = Client::builder()
-            .user_agent(USER_AGENT)
-            .build()
-            .expect("Failed to create HTTP client");
+        Self::with_http_settings(HttpSettings::default())
+    }
 
+    /// A client with default chart settings whose HTTP client is built from `http_settings`.
+    fn with_http_settings(http_settings: HttpSettings) -> Self {
         QuickchartClient {
-            client,
+            client: http_settings.build_client(),
+            #[cfg(feature = "blocking")]
+            blocking_client: std::sync::OnceLock::new(),
+            http_settings,
             base_url: Url::parse(BASE_URL).expect("Failed to parse base URL"),
+            fallback_base_urls: Vec::new(),
+            fallback_image: None,
+            retries: 0,
+            retry_base_delay: DEFAULT_RETRY_BASE_DELAY,
+            max_response_bytes: None,
             chart: String::new(),
+            chart_json: None,
             width: None,
             height: None,
             device_pixel_ratio: None,
+            scale: None,
+            invalid_aspect_ratio: None,
             background_color: None,
             version: None,
+            api_key: None,
+            extra_params: Vec::new(),
+            headers: Vec::new(),
             format: None,
+            default_format: None,
+            url_escape: UrlEscape::default(),
+            space_encoding: SpaceEncoding::default(),
+            base64_chart: false,
+            json_only: false,
+            options: ChartOptions::default(),
         }
     }
 
-    /// Set the Chart.js configuration as a JSON string. Both valid JSON and JavaScript object notation are supported.
-    pub fn chart(mut self, chart: String) -> Self {
-        self.chart = chart;
-        self
+    /// Create a client from an existing chart URL such as one built by
+    /// [`get_url()`](QuickchartClient::get_url), e.g. to change a chart someone shared.
+    ///
+    /// The host (and any path before `/chart`) becomes the base URL, and the `c`, `w`, `h`,
+    /// `devicePixelRatio`, `bkg`, `f`, `v` and `key` parameters, or their long names such as
+    /// `width`, set the matching builder fields. A base64-encoded `c` (`encoding=base64`) is
+    /// decoded and turns on [`base64_chart()`](QuickchartClient::base64_chart). Any other
+    /// parameters are kept as [`extra_param()`](QuickchartClient::extra_param)s.
+    ///
+    /// Returns [`QCError::EmptyChart`] if the URL has no chart config and
+    /// [`QCError::InvalidUrlParam`] if a parameter's value can't be read.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use quickchart_rs::QuickchartClient;
+    ///
+    /// let client = QuickchartClient::from_url("https://quickchart.io/chart?c={type:'bar'}&w=800")?
+    ///     .height(400);
+    ///
+    /// assert_eq!(client.get_chart(), "{type:'bar'}");
+    /// assert_eq!(client.get_width(), Some(800));
+    /// # Ok::<(), quickchart_rs::QCError>(())
+    /// ```
+    pub fn from_url(url: &str) -> Result<Self, QCError> {
+        let url = Url::parse(url)?;
+        let mut base = url.clone();
+        base.set_query(None);
+        base.set_fragment(None);
+        let path = base.path().trim_end_matches('/').to_string();
+        base.set_path(path.strip_suffix(&format!("/{}", CHART_ENDPOINT)).unwrap_or(&path));
+
+        let mut client = Self::new();
+        client.base_url = Self::parse_base_url(base.as_str())?;
+        let mut chart = None;
+        let mut base64_chart = false;
+        for (key, value) in url.query_pairs() {
+            let (key, value) = (key.into_owned(), value.into_owned());
+            let invalid = || QCError::InvalidUrlParam { name: key.clone(), value: value.clone() };
+            match key.as_str() {
+                "c" | "chart" => chart = Some(value),
+                "encoding" => base64_chart = value == "base64",
+                "w" | "width" => client.width = Some(value.parse().map_err(|_| invalid())?),
+                "h" | "height" => client.height = Some(value.parse().map_err(|_| invalid())?),
+                "devicePixelRatio" => {
+                    client.device_pixel_ratio = Some(value.parse().map_err(|_| invalid())?)
+                }
+                "bkg" | "backgroundColor" => client.background_color = Some(value),
+                "f" | "format" => client.format = Some(value),
+                "v" | "version" => client.version = Some(value),
+                "key" => client.api_key = Some(value),
+                _ => client.extra_params.push((key, value)),
+            }
+        }
+
+        let mut chart = chart.filter(|chart| !chart.trim().is_empty()).ok_or(QCError::EmptyChart)?;
+        if base64_chart {
+            let decoded = base64::engine::general_purpose::STANDARD
+                .decode(&chart)
+                .ok()
+                .and_then(|decoded| String::from_utf8(decoded).ok());
+            chart = decoded.ok_or_else(|| QCError::InvalidUrlParam {
+                name: "c".to_string(),
+                value: chart.clone(),
+            })?;
+            client.base64_chart = true;
+        }
+        Ok(client.chart(chart))
     }
 
-    pub fn width(mut self, width: usize) -> Self {
-        self.width = Some(width);
-        self
+    /// Create a client whose base URL is read from the `QUICKCHART_BASE_URL` environment
+    /// variable, e.g. to point local development or CI at a test server without code changes.
+    /// Falls back to `https://quickchart.io` when the variable is unset or empty, and returns
+    /// [`QCError::UrlParseError`] when it isn't a valid URL.
+    ///
+    /// The variable only sets the starting value: an explicit
+    /// [`base_url()`](QuickchartClient::base_url) call afterwards takes precedence over it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use quickchart_rs::QuickchartClient;
+    ///
+    /// // Uses QUICKCHART_BASE_URL if set, but always renders through localhost here
+    /// let client = QuickchartClient::new_from_env()?.base_url("http://localhost:3400")?;
+    /// # Ok::<(), quickchart_rs::QCError>(())
+    /// ```
+    pub fn new_from_env() -> Result<Self, QCError> {
+        Self::new().with_base_url_var(std::env::var(BASE_URL_ENV_VAR).ok())
     }
 
-    pub fn height(mut self, height: usize) -> Self {
-        self.height = Some(height);
-        self
+    /// Apply the value of [`BASE_URL_ENV_VAR`], keeping the current base URL if it's unset.
+    fn with_base_url_var(mut self, value: Option<String>) -> Result<Self, QCError> {
+        if let Some(value) = value.filter(|value| !value.trim().is_empty()) {
+            self.base_url = Self::parse_base_url(value.trim())?;
+        }
+        Ok(self)
     }
 
-    pub fn device_pixel_ratio(mut self, dpr: f32) -> Self {
-        self.device_pixel_ratio = Some(dpr);
-        self
+    /// Create a client that sends its async requests through `client` instead of building its
+    /// own, for connection pooling, proxy or TLS configuration the builder doesn't expose.
+    /// Chart builder methods work as usual on top of it.
+    ///
+    /// The client is used as-is: it doesn't get this crate's user agent, which the caller is
+    /// responsible for setting, and [`add_root_certificate()`](QuickchartClient::add_root_certificate),
+    /// [`timeout()`](QuickchartClient::timeout) and [`http1_only()`](QuickchartClient::http1_only)
+    /// have no effect on it. The blocking methods still build their own client.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use quickchart_rs::QuickchartClient;
+    ///
+    /// let http = reqwest::Client::builder()
+    ///     .user_agent("my-app/1.0")
+    ///     .pool_max_idle_per_host(4)
+    ///     .build()
+    ///     .unwrap();
+    /// let client = QuickchartClient::with_client(http).chart(r#"{"type":"bar"}"#.to_string());
+    /// ```
+    pub fn with_client(client: Client) -> Self {
+        Self::with_http_settings(HttpSettings {
+            custom_client: Some(client),
+            ..HttpSettings::default()
+        })
     }
 
-    /// Set the background color. Supports named colors ("transparent", "white"), HEX ("#ffffff"),
-    /// RGB ("rgb(255, 0, 0)"), and HSL ("hsl(0, 100%, 50%)") formats.
-    pub fn background_color(mut self, color: String) -> Self {
-        self.background_color = Some(color);
+    /// Trust an additional root certificate, e.g. the private CA that signed a self-hosted
+    /// QuickChart instance's certificate. The system trust store is still used as well.
+    ///
+    /// Build the certificate with [`Certificate::from_pem`] for PEM input (the
+    /// `-----BEGIN CERTIFICATE-----` text form) or [`Certificate::from_der`] for binary DER.
+    /// Rebuilds the internal HTTP client.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use quickchart_rs::QuickchartClient;
+    ///
+    /// let pem = std::fs::read("internal-ca.pem").unwrap();
+    /// let client = QuickchartClient::new()
+    ///     .add_root_certificate(reqwest::Certificate::from_pem(&pem).unwrap());
+    /// ```
+    pub fn add_root_certificate(mut self, certificate: Certificate) -> Self {
+        self.http_settings.root_certificates.push(certificate);
+        self.rebuild_http_client();
         self
     }
 
-    pub fn version(mut self, version: String) -> Self {
-        self.version = Some(version);
+    /// Give up on a request that hasn't completed within `timeout`, from connecting until the
+    /// whole response has been read. A timed-out request fails with [`QCError::HttpError`]
+    /// (for which `is_timeout()` is true), or moves on to the next
+    /// [`fallback_base_urls()`](QuickchartClient::fallback_base_urls) host. By default requests
+    /// never time out. Rebuilds the internal HTTP client.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.http_settings.timeout = Some(timeout);
+        self.rebuild_http_client();
         self
     }
 
-    pub fn format(mut self, format: String) -> Self {
-        self.format = Some(format);
+    /// Retry a POST request up to `max` more times when QuickChart is rate limiting (429) or
+    /// failing (5xx), or can't be reached or times out. Other 4xx errors fail immediately.
+    ///
+    /// Each retry goes through the primary host and any
+    /// [`fallback_base_urls()`](QuickchartClient::fallback_base_urls) again, waiting the
+    /// response's `Retry-After` seconds if it has one, up to 60 seconds, otherwise an exponential
+    /// backoff of [`retry_base_delay()`](QuickchartClient::retry_base_delay) doubled after each
+    /// attempt. Disabled by default.
+    pub fn retries(mut self, max: u32) -> Self {
+        self.retries = max;
         self
     }
 
-    fn parse_chart(chart: &str) -> serde_json::Value {
-        serde_json::from_str::<serde_json::Value>(chart)
-            .unwrap_or_else(|_| serde_json::Value::String(chart.to_string()))
+    /// Fail with [`QCError::ResponseTooLarge`] instead of reading an image larger than `limit`
+    /// bytes, so a misbehaving server can't exhaust memory. Applies to
+    /// [`post()`](QuickchartClient::post), [`write_to()`](QuickchartClient::write_to), the
+    /// methods built on them and `post_blocking()`. The response's `Content-Length` is checked
+    /// first, then the body is counted as it streams in, so bodies without a length are cut off
+    /// too. [`write_to()`](QuickchartClient::write_to) may already have written part of the body
+    /// when the limit is hit. Unlimited by default.
+    ///
+    /// Error responses are never read past the limit either: their body is truncated to it in
+    /// the [`QCError::ApiError`] message, and to 64 KiB in any case.
+    pub fn max_response_bytes(mut self, limit: usize) -> Self {
+        self.max_response_bytes = Some(limit);
+        self
     }
 
-    fn build_json_body(&self) -> serde_json::Value {
-        let chart_value = Self::parse_chart(&self.chart);
-        let mut json_body = serde_json::json!({ "chart": chart_value });
+    /// The wait before the first retry, doubled for each later one. Defaults to 500ms.
+    pub fn retry_base_delay(mut self, delay: Duration) -> Self {
+        self.retry_base_delay = delay;
+        self
+    }
 
-        if let Some(w) = self.width {
-            json_body["width"] = serde_json::Value::Number(w.into());
-        }
-        if let Some(h) = self.height {
-            json_body["height"] = serde_json::Value::Number(h.into());
-        }
-        if let Some(dpr) = self.device_pixel_ratio {
-            json_body["devicePixelRatio"] =
-                serde_json::Value::Number(serde_json::Number::from_f64(dpr as f64).unwrap());
-        }
-        if let Some(ref bkg) = self.background_color {
-            json_body["backgroundColor"] = serde_json::Value::String(bkg.clone());
-        }
-        if let Some(ref v) = self.version {
-            json_body["version"] = serde_json::Value::String(v.clone());
-        }
-        if let Some(ref f) = self.format {
-            json_body["format"] = serde_json::Value::String(f.clone());
-        }
+    /// Only speak HTTP/1.1, for proxies or load balancers in front of a self-hosted QuickChart
+    /// that mishandle HTTP/2. By default the HTTP version is negotiated with the server.
+    /// Rebuilds the internal HTTP client.
+    pub fn http1_only(mut self) -> Self {
+        self.http_settings.http1_only = true;
+        self.rebuild_http_client();
+        self
+    }
 
-        json_body
+    /// Whether to ask for compressed responses (`Accept-Encoding: gzip, br`) and decompress them
+    /// transparently, which saves bandwidth on large SVG charts. On by default, for the
+    /// encodings whose crate features (`gzip` and `brotli`, both default) are enabled; with
+    /// neither feature this has no effect. Rebuilds the internal HTTP client.
+    pub fn compression(mut self, enabled: bool) -> Self {
+        self.http_settings.compression_disabled = !enabled;
+        self.rebuild_http_client();
+        self
     }
 
-    fn compact_chart(chart: &str) -> String {
-        // Try to parse as JSON
-        if let Ok(chart_json) = serde_json::from_str::<serde_json::Value>(chart) {
-            return serde_json::to_string(&chart_json).unwrap_or_else(|_| chart.to_string());
+    /// Rebuild the HTTP client after a change to `http_settings`.
+    fn rebuild_http_client(&mut self) {
+        self.client = self.http_settings.build_client();
+        #[cfg(feature = "blocking")]
+        {
+            self.blocking_client = std::sync::OnceLock::new();
         }
-
-        // For non-JSON
-        chart
-            .chars()
-            .fold((String::with_capacity(chart.len()), false), |(mut acc, prev_space), ch| {
-                let is_whitespace = ch.is_whitespace();
-                if is_whitespace && !prev_space {
-                    acc.push(' ');
-                } else if !is_whitespace {
-                    acc.push(ch);
-                }
-                (acc, is_whitespace)
-            })
-            .0
-            .trim()
-            .to_string()
     }
 
-    /// Generate a chart URL with all configured parameters as query parameters.
+    /// Send requests to another QuickChart instance instead of `https://quickchart.io`, such as
+    /// a self-hosted `http://localhost:3400`. Endpoints are resolved under the URL's path, so
+    /// `https://example.com/quickchart` serves charts from `https://example.com/quickchart/chart`.
+    ///
+    /// Returns [`QCError::UrlParseError`] if the URL is invalid.
     ///
     /// # Example
     ///
@@ -185,120 +718,2444 @@ impl QuickchartClient {
     /// use quickchart_rs::QuickchartClient;
     ///
     /// let client = QuickchartClient::new()
-    ///     .chart(r#"{"type":"bar","data":{"labels":["A","B"],"datasets":[{"data":[1,2]}]}}"#.to_string())
-    ///     .width(800)
-    ///     .height(400);
+    ///     .base_url("http://localhost:3400")
+    ///     .unwrap()
+    ///     .chart(r#"{"type":"bar"}"#.to_string());
     ///
-    /// let url = client.get_url().unwrap();
-    /// assert!(url.starts_with("https://quickchart.io/chart"));
-    /// assert!(url.contains("w=800"));
-    /// assert!(url.contains("h=400"));
+    /// assert!(client.get_url().unwrap().starts_with("http://localhost:3400/chart?"));
     /// ```
-    pub fn get_url(&self) -> Result<String, QCError> {
-        let compacted_chart = Self::compact_chart(&self.chart);
-        let mut url = self.base_url.join(CHART_ENDPOINT)?;
-
-        {
-            let mut query = url.query_pairs_mut();
-            query.append_pair("c", &compacted_chart);
+    pub fn base_url(mut self, url: impl AsRef<str>) -> Result<Self, QCError> {
+        self.base_url = Self::parse_base_url(url.as_ref())?;
+        Ok(self)
+    }
 
-            if let Some(w) = self.width {
-                query.append_pair("w", &w.to_string());
-            }
-            if let Some(h) = self.height {
-                query.append_pair("h", &h.to_string());
-            }
-            if let Some(dpr) = self.device_pixel_ratio {
-                query.append_pair("devicePixelRatio", &dpr.to_string());
-            }
-            if let Some(ref bkg) = self.background_color {
-                query.append_pair("bkg", bkg);
-            }
-            if let Some(ref v) = self.version {
-                query.append_pair("v", v);
-            }
-            if let Some(ref f) = self.format {
-                query.append_pair("f", f);
-            }
+    /// Parse a base URL, adding a trailing slash so endpoints are joined under its path rather
+    /// than replacing the last segment.
+    fn parse_base_url(url: &str) -> Result<Url, QCError> {
+        let mut url = Url::parse(url)?;
+        if !url.path().ends_with('/') {
+            let path = format!("{}/", url.path());
+            url.set_path(&path);
         }
+        Ok(url)
+    }
 
-        Ok(url.to_string())
+    /// Hosts to fail over to, in order, when the primary QuickChart host can't serve a POST
+    /// request (used by [`post()`](QuickchartClient::post),
+    /// [`get_short_url()`](QuickchartClient::get_short_url) and
+    /// [`to_file()`](QuickchartClient::to_file)).
+    ///
+    /// The next host is tried only when the current one can't be connected to, times out, or
+    /// responds with a 5xx status. A 4xx response is returned immediately since the same request
+    /// would fail everywhere. If every host fails, the last error is returned.
+    /// [`get_url()`](QuickchartClient::get_url) always uses the primary host.
+    ///
+    /// Returns [`QCError::UrlParseError`] if any of the URLs is invalid.
+    pub fn fallback_base_urls(mut self, urls: Vec<String>) -> Result<Self, QCError> {
+        self.fallback_base_urls = urls
+            .iter()
+            .map(|url| Self::parse_base_url(url))
+            .collect::<Result<_, _>>()?;
+        Ok(self)
     }
 
-    /// Create a short URL for the chart via POST request to `/chart/create`.
+    /// Image bytes to return instead of an error when QuickChart can't be reached, e.g. a "chart
+    /// unavailable" placeholder for dashboards that should degrade gracefully.
+    ///
+    /// [`post()`](QuickchartClient::post), [`post_into()`](QuickchartClient::post_into) and the
+    /// methods built on them, such as [`to_file()`](QuickchartClient::to_file), return the
+    /// placeholder when every host (including any
+    /// [`fallback_base_urls()`](QuickchartClient::fallback_base_urls)) can't be connected to,
+    /// times out, or responds with a 5xx status, or when the connection fails while the image is
+    /// being downloaded. A 4xx response means the config itself was rejected and is still
+    /// returned as an error, as are errors building the request. Short URLs aren't affected.
+    pub fn fallback_image(mut self, bytes: Vec<u8>) -> Self {
+        self.fallback_image = Some(bytes);
+        self
+    }
+
+    /// Set the Chart.js configuration as a JSON string. Both valid JSON and JavaScript object notation are supported.
+    pub fn chart(mut self, chart: String) -> Self {
+        self.chart = chart;
+        self.chart_json = None;
+        self.options.sparkline = false;
+        self
+    }
+
+    /// Set the Chart.js configuration from the contents of a file, such as a `.json` config kept
+    /// alongside the code. The file is read as-is, so JS-notation files work too. Returns
+    /// [`QCError::IoError`] if it can't be read.
     ///
     /// # Example
     ///
     /// ```no_run
     /// use quickchart_rs::QuickchartClient;
     ///
-    /// let client = QuickchartClient::new()
-    ///     .chart(r#"{"type":"bar","data":{"labels":["A","B"],"datasets":[{"data":[1,2]}]}}"#.to_string())
-    ///     .get_short_url().await?;
+    /// let client = QuickchartClient::new().chart_from_file("charts/revenue.json")?.width(800);
+    /// # Ok::<(), quickchart_rs::QCError>(())
     /// ```
-    pub async fn get_short_url(&self) -> Result<String, QCError> {
-        let json_body = self.build_json_body();
-        let response = self
-            .send_post_request(CREATE_ENDPOINT, &json_body)
-            .await?;
+    pub fn chart_from_file(self, path: impl AsRef<Path>) -> Result<Self, QCError> {
+        let chart = std::fs::read_to_string(path)?;
+        Ok(self.chart(chart))
+    }
 
-        let response_text = response.text().await?;
-        let response_json: serde_json::Value = serde_json::from_str(&response_text)?;
+    /// Set the chart type, e.g. `"line"`, overriding any `type` in the config. Together with
+    /// [`labels()`](QuickchartClient::labels) and [`dataset()`](QuickchartClient::dataset) this
+    /// builds a chart without writing the config by hand.
+    pub fn chart_type(mut self, chart_type: String) -> Self {
+        self.options.chart_type = Some(chart_type);
+        self
+    }
 
-        response_json
-            .get("url")
-            .and_then(|v| v.as_str())
-            .map(|url| url.trim_matches('"').trim_matches('\'').to_string())
-            .ok_or_else(|| QCError::MissingField("url".to_string()))
+    /// Set the x-axis labels, overriding any `data.labels` in the config.
+    pub fn labels(mut self, labels: Vec<String>) -> Self {
+        self.options.labels = Some(labels);
+        self
     }
 
-    async fn send_post_request(
-        &self,
-        endpoint: &str,
-        json_body: &serde_json::Value,
-    ) -> Result<reqwest::Response, QCError> {
-        let response = self
-            .client
-            .post(self.base_url.join(endpoint)?.to_string())
-            .header("Content-Type", "application/json")
-            .body(serde_json::to_string(json_body)?)
-            .send()
-            .await?;
+    /// Add a dataset named `label`. Datasets are appended to `data.datasets` in the order they
+    /// were added, after any datasets already in the config. If no chart has been set, one is
+    /// built from [`chart_type()`](QuickchartClient::chart_type),
+    /// [`labels()`](QuickchartClient::labels) and the datasets. Has no effect on JS-notation
+    /// configs.
+    ///
+    /// ```
+    /// use quickchart_rs::QuickchartClient;
+    ///
+    /// let client = QuickchartClient::new()
+    ///     .chart_type("line".to_string())
+    ///     .labels(vec!["Q1".to_string(), "Q2".to_string()])
+    ///     .dataset("Revenue".to_string(), vec![120.0, 150.0])
+    ///     .dataset("Costs".to_string(), vec![80.0, 95.0]);
+    ///
+    /// let url = client.get_url_parsed().unwrap();
+    /// let (_, chart) = url.query_pairs().find(|(key, _)| key == "c").unwrap();
+    /// let config: serde_json::Value = serde_json::from_str(&chart).unwrap();
+    /// assert_eq!(config["type"], "line");
+    /// assert_eq!(config["data"]["datasets"][1]["label"], "Costs");
+    /// ```
+    pub fn dataset(mut self, label: String, data: Vec<f64>) -> Self {
+        self.options.datasets.push((label, data));
+        self
+    }
 
-        response.error_for_status().map_err(Into::into)
+    /// Set the Chart.js configuration from a typed [`Chart`](crate::Chart), stored as a JSON
+    /// value like [`chart_value()`](QuickchartClient::chart_value).
+    pub fn chart_typed(self, chart: crate::Chart) -> Self {
+        let chart = serde_json::to_value(&chart).expect("chart config serializes to JSON");
+        self.chart_value(chart)
     }
 
-    /// Download the chart image as bytes via POST request.
+    /// Set the Chart.js configuration as a JSON value, e.g. one built with
+    /// [`serde_json::json!`]. The value is stored as-is and sent without being serialized to a
+    /// string and parsed back.
     ///
     /// # Example
     ///
-    /// ```no_run
-    /// let client = QuickchartClient::new()
-    ///     .chart(r#"{"type":"bar","data":{"labels":["A","B"],"datasets":[{"data":[1,2]}]}}"#.to_string())
-    ///     .post().await?;
+    /// ```
+    /// use quickchart_rs::QuickchartClient;
     ///
+    /// let client = QuickchartClient::new().chart_value(serde_json::json!({
+    ///     "type": "bar",
+    ///     "data": { "labels": ["A", "B"], "datasets": [{ "data": [1, 2] }] }
+    /// }));
+    ///
+    /// assert!(client.get_url().is_ok());
     /// ```
-    pub async fn post(&self) -> Result<Vec<u8>, QCError> {
-        let json_body = self.build_json_body();
-        let response = self.send_post_request(CHART_ENDPOINT, &json_body).await?;
-        Ok(response.bytes().await?.to_vec())
+    pub fn chart_value(mut self, value: serde_json::Value) -> Self {
+        self.chart = String::new();
+        self.chart_json = Some(value);
+        self.options.sparkline = false;
+        self
     }
 
-    /// Download the chart image and save it directly to a file. Convenience method that combines
-    /// [`post()`](QuickchartClient::post) and file writing.
+    /// Mutable access to the chart config as a parsed JSON value, for direct edits beyond what
+    /// the builder helpers cover. The edited value is serialized on every subsequent request.
+    ///
+    /// The config is parsed on first access. A JS-notation config is converted to JSON, which
+    /// works for plain literals (unquoted keys, single quotes, comments, trailing commas) but
+    /// returns [`QCError::InvalidConfig`] if it contains functions or other expressions. An empty
+    /// config starts as `{}`. Calling [`chart()`](QuickchartClient::chart) afterwards discards the
+    /// edits.
     ///
     /// # Example
     ///
-    /// ```no_run
-    /// let client = QuickchartClient::new()
-    ///     .chart(r#"{"type":"bar","data":{"labels":["A","B"],"datasets":[{"data":[1,2]}]}}"#.to_string())
-    ///     .to_file("output.png")
-    ///     .await?;
     /// ```
-    pub async fn to_file(&self, path: impl AsRef<Path>) -> Result<(), QCError> {
-        let image_bytes = self.post().await?;
-        std::fs::write(path, image_bytes)?;
-        Ok(())
+    /// use quickchart_rs::QuickchartClient;
+    ///
+    /// let mut client = QuickchartClient::new().chart("{type: 'bar', data: {labels: ['A']}}".to_string());
+    /// client.config_mut().unwrap()["type"] = "line".into();
+    ///
+    /// assert_eq!(client.config_mut().unwrap()["type"], "line");
+    /// ```
+    pub fn config_mut(&mut self) -> Result<&mut serde_json::Value, QCError> {
+        let chart_json = match self.chart_json.take() {
+            Some(chart_json) => chart_json,
+            None if self.chart.trim().is_empty() => serde_json::json!({}),
+            None => serde_json::from_str(&self.chart)
+                .or_else(|_| js_object::parse(&self.chart))
+                .map_err(QCError::InvalidConfig)?,
+        };
+        Ok(self.chart_json.insert(chart_json))
+    }
+
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = Some(width);
+        self.invalid_aspect_ratio = None;
+        self
     }
-}
 
+    pub fn height(mut self, height: usize) -> Self {
+        self.height = Some(height);
+        self.invalid_aspect_ratio = None;
+        self
+    }
+
+    /// Set the width and height at once.
+    pub fn size(self, width: usize, height: usize) -> Self {
+        self.width(width).height(height)
+    }
+
+    /// Set the width and derive the height from a width-to-height ratio, e.g. `16.0 / 9.0`,
+    /// rounded to the nearest pixel. The ratio must be positive and finite: for any other value
+    /// only the width is set, and [`get_url()`](QuickchartClient::get_url) and requests fail
+    /// with [`QCError::InvalidAspectRatio`] until the size is set again, with this or any other
+    /// dimension setter.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use quickchart_rs::QuickchartClient;
+    ///
+    /// let client = QuickchartClient::new().aspect_ratio(800, 16.0 / 9.0);
+    /// assert_eq!(client.get_height(), Some(450));
+    /// ```
+    pub fn aspect_ratio(mut self, width: usize, ratio: f32) -> Self {
+        if !ratio.is_finite() || ratio <= 0.0 {
+            self = self.width(width);
+            self.invalid_aspect_ratio = Some(ratio);
+            return self;
+        }
+        let height = (width as f64 / ratio as f64).round() as usize;
+        self.size(width, height)
+    }
+
+    /// Clear any width and height so neither is sent and QuickChart picks the size itself, e.g.
+    /// on a client forked from a base that set dimensions.
+    pub fn auto_size(mut self) -> Self {
+        self.width = None;
+        self.height = None;
+        self.invalid_aspect_ratio = None;
+        self
+    }
+
+    /// Render at `dpr` times the width and height, e.g. 2.0 for sharp images on high-DPI
+    /// screens. Must be positive and finite: [`get_url()`](QuickchartClient::get_url) and
+    /// requests otherwise fail with [`QCError::InvalidDevicePixelRatio`].
+    pub fn device_pixel_ratio(mut self, dpr: f32) -> Self {
+        self.device_pixel_ratio = Some(dpr);
+        self
+    }
+
+    /// Zoom the chart canvas by `scale`, e.g. 1.5 for a chart half again as wide and tall, by
+    /// multiplying the width and height sent to QuickChart (500x300 where unset, QuickChart's
+    /// defaults). Must be positive and finite: [`get_url()`](QuickchartClient::get_url) and
+    /// requests otherwise fail with [`QCError::InvalidScale`].
+    ///
+    /// This differs from [`device_pixel_ratio()`](QuickchartClient::device_pixel_ratio), which
+    /// keeps the layout and renders it with more pixels, so text and lines stay the same size
+    /// relative to the chart. Scaling instead gives Chart.js a bigger canvas to lay out: fonts
+    /// and line widths keep their pixel size, so they get smaller relative to the chart and more
+    /// ticks and labels fit. Use the pixel ratio for sharper print output of the same chart, and
+    /// scale to fit a denser chart into a larger area.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use quickchart_rs::QuickchartClient;
+    ///
+    /// let client = QuickchartClient::new()
+    ///     .chart(r#"{"type":"bar"}"#.to_string())
+    ///     .width(400)
+    ///     .scale(1.5);
+    ///
+    /// assert!(client.get_url().unwrap().contains("w=600&h=450"));
+    /// ```
+    pub fn scale(mut self, scale: f32) -> Self {
+        self.scale = Some(scale);
+        self
+    }
+
+    /// The width and height sent to QuickChart, with [`scale()`](QuickchartClient::scale)
+    /// applied.
+    fn resolved_size(&self) -> (Option<usize>, Option<usize>) {
+        let (default_width, default_height) = self.default_size();
+        let (width, height) = if self.options.sparkline {
+            (self.width.or(Some(default_width)), self.height.or(Some(default_height)))
+        } else {
+            (self.width, self.height)
+        };
+        let Some(scale) = self.scale.filter(|scale| scale.is_finite() && *scale > 0.0) else {
+            return (width, height);
+        };
+        let scaled = |size: usize| (size as f64 * scale as f64).round().max(1.0) as usize;
+        (
+            Some(scaled(width.unwrap_or(default_width))),
+            Some(scaled(height.unwrap_or(default_height))),
+        )
+    }
+
+    /// The size rendered when none is set: 100x30 for a
+    /// [`sparkline()`](QuickchartClient::sparkline), QuickChart's 500x300 otherwise.
+    fn default_size(&self) -> (usize, usize) {
+        if self.options.sparkline {
+            (SPARKLINE_WIDTH, SPARKLINE_HEIGHT)
+        } else {
+            (DEFAULT_WIDTH, DEFAULT_HEIGHT)
+        }
+    }
+
+    /// Set the background color. Supports named colors ("transparent", "white"), HEX ("#ffffff"),
+    /// RGB ("rgb(255, 0, 0)"), and HSL ("hsl(0, 100%, 50%)") formats.
+    pub fn background_color(mut self, color: String) -> Self {
+        self.background_color = Some(color);
+        self
+    }
+
+    /// Set the background color like [`background_color()`](QuickchartClient::background_color),
+    /// but check it first: a named CSS color, `#` hex with 3, 4, 6 or 8 digits, or `rgb()`,
+    /// `rgba()`, `hsl()` or `hsla()` with in-range components. Returns
+    /// [`QCError::InvalidColor`] otherwise, instead of a broken chart later.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use quickchart_rs::{QCError, QuickchartClient};
+    ///
+    /// assert!(QuickchartClient::new().background_color_checked("#1f4e79".to_string()).is_ok());
+    /// assert!(matches!(
+    ///     QuickchartClient::new().background_color_checked("rgb(300,,)".to_string()),
+    ///     Err(QCError::InvalidColor(_))
+    /// ));
+    /// ```
+    pub fn background_color_checked(self, color: String) -> Result<Self, QCError> {
+        if !color::is_valid_color(&color) {
+            return Err(QCError::InvalidColor(color));
+        }
+        Ok(self.background_color(color))
+    }
+
+    pub fn version(mut self, version: String) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    /// Set the Chart.js version, sent as the `v` query parameter or `version` field, without
+    /// risking a typo such as `"v3"`. [`version()`](QuickchartClient::version) still accepts any
+    /// string, e.g. a minor version QuickChart adds later.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use quickchart_rs::{ChartVersion, QuickchartClient};
+    ///
+    /// let client = QuickchartClient::new()
+    ///     .chart(r#"{"type":"bar"}"#.to_string())
+    ///     .version_typed(ChartVersion::V4);
+    ///
+    /// assert!(client.get_url().unwrap().ends_with("&v=4"));
+    /// ```
+    pub fn version_typed(self, version: ChartVersion) -> Self {
+        self.version(version.as_str().to_string())
+    }
+
+    /// Authenticate with a QuickChart account's API key, which lifts the free tier's rate
+    /// limits and watermark. The key is sent as the `key` query parameter in
+    /// [`get_url()`](QuickchartClient::get_url) and as the `key` field of POST bodies, as
+    /// QuickChart documents. Anyone with a generated URL can read the key, so prefer short URLs
+    /// or POST requests for charts shared publicly.
+    pub fn api_key(mut self, key: String) -> Self {
+        self.api_key = Some(key);
+        self
+    }
+
+    /// Send an option this crate doesn't model yet, as a query parameter in
+    /// [`get_url()`](QuickchartClient::get_url) and a string field of POST bodies.
+    ///
+    /// Extra parameters take precedence over the crate's own for the same key, e.g. `w`
+    /// replaces [`width()`](QuickchartClient::width) in URLs. The chart config can't be
+    /// replaced this way: a `c` parameter is left out of URLs and a `chart` field out of bodies.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use quickchart_rs::QuickchartClient;
+    ///
+    /// let url = QuickchartClient::new()
+    ///     .chart(r#"{"type":"bar"}"#.to_string())
+    ///     .extra_param("scale".to_string(), "2".to_string())
+    ///     .get_url()
+    ///     .unwrap();
+    /// assert!(url.ends_with("&scale=2"));
+    /// ```
+    pub fn extra_param(mut self, key: String, value: String) -> Self {
+        self.extra_params.push((key, value));
+        self
+    }
+
+    /// Add an HTTP header to the POST requests sent to QuickChart, e.g. an `Authorization` header
+    /// for a proxy in front of a self-hosted instance, or a tracing header. Headers are sent in
+    /// the order added; adding a name twice sends both values. A `Content-Type` header replaces
+    /// the default `application/json`. GET requests, such as
+    /// [`qr_post()`](QuickchartClient::qr_post), don't send them.
+    ///
+    /// Names and values are checked before a request is sent: an invalid one makes requests
+    /// fail with [`QCError::InvalidHeader`]. Values aren't shown in the client's `Debug` output.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use quickchart_rs::QuickchartClient;
+    ///
+    /// let client = QuickchartClient::new()
+    ///     .chart(r#"{"type":"bar"}"#.to_string())
+    ///     .header("Authorization".to_string(), "Bearer abc123".to_string())
+    ///     .header("X-Request-Id".to_string(), "7f3a".to_string());
+    /// ```
+    pub fn header(mut self, name: String, value: String) -> Self {
+        self.headers.push((name, value));
+        self
+    }
+
+    /// The headers added with [`header()`](QuickchartClient::header), checked.
+    pub(crate) fn custom_headers(&self) -> Result<reqwest::header::HeaderMap, QCError> {
+        let mut headers = reqwest::header::HeaderMap::new();
+        for (name, value) in &self.headers {
+            let invalid = || QCError::InvalidHeader(name.clone());
+            let header_name = reqwest::header::HeaderName::from_bytes(name.as_bytes())
+                .map_err(|_| invalid())?;
+            let header_value =
+                reqwest::header::HeaderValue::from_str(value).map_err(|_| invalid())?;
+            headers.append(header_name, header_value);
+        }
+        Ok(headers)
+    }
+
+    /// Set the output format from any string QuickChart accepts. Prefer
+    /// [`format_typed()`](QuickchartClient::format_typed), which can't be misspelled.
+    pub fn format(mut self, format: String) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    /// Set the output format, sent as the `f` query parameter or `format` field.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use quickchart_rs::{ImageFormat, QuickchartClient};
+    ///
+    /// let client = QuickchartClient::new()
+    ///     .chart(r#"{"type":"bar"}"#.to_string())
+    ///     .format_typed(ImageFormat::Svg);
+    ///
+    /// assert!(client.get_url().unwrap().contains("f=svg"));
+    /// ```
+    pub fn format_typed(self, format: ImageFormat) -> Self {
+        self.format(format.as_str().to_string())
+    }
+
+    /// Set the format used when [`format()`](QuickchartClient::format) isn't called, e.g. to
+    /// make every chart forked from a shared base client render as SVG. An explicit `format()`
+    /// always wins, on this client or any clone of it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use quickchart_rs::QuickchartClient;
+    ///
+    /// let base = QuickchartClient::new().default_format("svg".to_string());
+    ///
+    /// let svg = base.clone().chart("{type:'bar'}".to_string());
+    /// let png = base.clone().chart("{type:'bar'}".to_string()).format("png".to_string());
+    ///
+    /// assert!(svg.get_url().unwrap().contains("f=svg"));
+    /// assert!(png.get_url().unwrap().contains("f=png"));
+    /// ```
+    pub fn default_format(mut self, format: String) -> Self {
+        self.default_format = Some(format);
+        self
+    }
+
+    /// Choose how the chart config is percent-encoded in [`get_url()`](QuickchartClient::get_url).
+    /// Defaults to [`UrlEscape::Form`].
+    pub fn url_escape(mut self, url_escape: UrlEscape) -> Self {
+        self.url_escape = url_escape;
+        self
+    }
+
+    /// Choose whether spaces in [`get_url()`](QuickchartClient::get_url)'s query are written as
+    /// `+` or `%20`. Unlike [`UrlEscape::NonAlphanumeric`], which percent-encodes every
+    /// punctuation character of the chart config, this only changes spaces, and in every
+    /// parameter. Defaults to [`SpaceEncoding::Plus`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use quickchart_rs::{QuickchartClient, SpaceEncoding};
+    ///
+    /// let client = QuickchartClient::new()
+    ///     .chart("{type: 'bar'}".to_string())
+    ///     .space_encoding(SpaceEncoding::Percent);
+    ///
+    /// assert!(client.get_url().unwrap().contains("c=%7Btype%3A%20%27bar%27%7D"));
+    /// ```
+    pub fn space_encoding(mut self, space_encoding: SpaceEncoding) -> Self {
+        self.space_encoding = space_encoding;
+        self
+    }
+
+    /// Base64-encode the chart config in [`get_url()`](QuickchartClient::get_url) and add
+    /// `encoding=base64`, which avoids percent-escaping every brace and quote and so usually
+    /// gives a noticeably shorter URL. Disabled by default.
+    ///
+    /// The config is not compressed: QuickChart's `encoding` parameter only takes `url` and
+    /// `base64`, and it can't read a gzipped config.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use quickchart_rs::QuickchartClient;
+    ///
+    /// let url = QuickchartClient::new()
+    ///     .chart(r#"{"type":"bar"}"#.to_string())
+    ///     .base64_chart(true)
+    ///     .get_url()
+    ///     .unwrap();
+    /// assert!(url.ends_with("&encoding=base64"));
+    /// ```
+    pub fn base64_chart(mut self, base64_chart: bool) -> Self {
+        self.base64_chart = base64_chart;
+        self
+    }
+
+    /// Refuse to send configs that aren't strict JSON, for deployments where JS-notation configs,
+    /// which can carry executable callbacks, must never reach QuickChart.
+    ///
+    /// When enabled, [`get_url()`](QuickchartClient::get_url),
+    /// [`url_parts()`](QuickchartClient::url_parts) and every method that sends a request
+    /// return [`QCError::InvalidConfig`] unless the config, with builder options merged in,
+    /// parses as JSON. This also rejects helpers that add JavaScript, such as
+    /// [`number_format()`](QuickchartClient::number_format). Disabled by default.
+    pub fn json_only(mut self, json_only: bool) -> Self {
+        self.json_only = json_only;
+        self
+    }
+
+    /// Finish building and check the client is ready to use: a chart config is set and the
+    /// settings pass the same checks [`get_url()`](QuickchartClient::get_url) and requests run,
+    /// such as [`json_only()`](QuickchartClient::json_only) and the device pixel ratio range.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use quickchart_rs::{QCError, QuickchartClient};
+    ///
+    /// let client = QuickchartClient::new().chart(r#"{"type":"bar"}"#.to_string()).build();
+    /// assert!(client.is_ok());
+    ///
+    /// assert!(matches!(QuickchartClient::new().build(), Err(QCError::EmptyChart)));
+    /// ```
+    pub fn build(self) -> Result<Self, QCError> {
+        self.check_config()?;
+        Ok(self)
+    }
+
+    /// Pass the builder through `f`, for configuration that depends on runtime conditions
+    /// without breaking the chain.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use quickchart_rs::QuickchartClient;
+    ///
+    /// let dark = true;
+    /// let client = QuickchartClient::new()
+    ///     .chart(r#"{"type":"bar"}"#.to_string())
+    ///     .apply(|c| if dark { c.background_color("black".to_string()) } else { c })
+    ///     .width(600);
+    ///
+    /// assert!(client.get_url().unwrap().contains("bkg=black"));
+    /// ```
+    pub fn apply(self, f: impl FnOnce(Self) -> Self) -> Self {
+        f(self)
+    }
+
+    /// Make the configured width and height authoritative by turning off Chart.js responsive
+    /// sizing. Sets `options.responsive` and `options.maintainAspectRatio` to `false`, merged into
+    /// any existing options. Has no effect on JS-notation configs.
+    pub fn fixed_size(mut self) -> Self {
+        self.options.fixed_size = true;
+        self
+    }
+
+    /// Keep the config's Chart.js animations.
+    ///
+    /// By default `options.animation` is set to `false` on JSON configs, because QuickChart
+    /// captures a single frame and animated elements can otherwise come out partially drawn. Use
+    /// this to send the animation settings unchanged. JS-notation configs are never modified.
+    pub fn keep_animation(mut self) -> Self {
+        self.options.keep_animation = true;
+        self
+    }
+
+    /// Set the grid line color on both axes, merged into the existing axis config. Has no effect
+    /// on JS-notation configs.
+    pub fn grid_color(mut self, color: String) -> Self {
+        self.options.grid_color = Some(color);
+        self
+    }
+
+    /// Draw dashed grid lines on both axes, given as alternating dash and gap lengths in pixels
+    /// (e.g. `vec![4, 4]`). Merged into the existing axis config. Has no effect on JS-notation
+    /// configs.
+    pub fn grid_dash(mut self, dash: Vec<u32>) -> Self {
+        self.options.grid_dash = Some(dash);
+        self
+    }
+
+    /// Space x-axis ticks `step` units apart (`ticks.stepSize`). Has no effect on JS-notation
+    /// configs.
+    pub fn x_tick_step(mut self, step: f64) -> Self {
+        self.options.x_tick_step = Some(step);
+        self
+    }
+
+    /// Space y-axis ticks `step` units apart (`ticks.stepSize`). Has no effect on JS-notation
+    /// configs.
+    pub fn y_tick_step(mut self, step: f64) -> Self {
+        self.options.y_tick_step = Some(step);
+        self
+    }
+
+    /// Show at most `max` ticks on the x axis (`ticks.maxTicksLimit`). Has no effect on
+    /// JS-notation configs.
+    pub fn x_max_ticks(mut self, max: u32) -> Self {
+        self.options.x_max_ticks = Some(max);
+        self
+    }
+
+    /// Show at most `max` ticks on the y axis (`ticks.maxTicksLimit`). Has no effect on
+    /// JS-notation configs.
+    pub fn y_max_ticks(mut self, max: u32) -> Self {
+        self.options.y_max_ticks = Some(max);
+        self
+    }
+
+    /// Rotate the x-axis tick labels by `degrees` so long category labels fit, and add bottom
+    /// layout padding (up to 30px at 90 degrees) so they aren't clipped.
+    ///
+    /// Sets the x axis `ticks.minRotation` and `ticks.maxRotation`. The padding is added to any
+    /// `options.layout.padding` already in the config rather than replacing it; a single numeric
+    /// padding is expanded into per-side values. Has no effect on JS-notation configs.
+    pub fn rotate_labels(mut self, degrees: i32) -> Self {
+        self.options.label_rotation = Some(degrees);
+        self
+    }
+
+    /// Maximize reproducibility of the rendered image, e.g. for comparing against golden images
+    /// in tests. Output can still change when QuickChart itself is upgraded.
+    ///
+    /// This:
+    /// - disables animations, undoing [`keep_animation()`](QuickchartClient::keep_animation)
+    /// - applies [`fixed_size()`](QuickchartClient::fixed_size)
+    /// - gives each dataset without a `backgroundColor`/`borderColor` an explicit color from a
+    ///   fixed palette, by position, instead of relying on the server's color assignment
+    /// - pins the Chart.js version to `2.9.4` (QuickChart's default) unless one is set
+    /// - sets width 500, height 300 (100x30 for a [`sparkline()`](QuickchartClient::sparkline))
+    ///   and device pixel ratio 2.0 (QuickChart's defaults) for any that aren't already set
+    ///
+    /// Color and option changes only apply to JSON configs.
+    pub fn deterministic(mut self) -> Self {
+        self.options.keep_animation = false;
+        self.options.fixed_size = true;
+        self.options.assign_colors = true;
+        self.version.get_or_insert_with(|| DEFAULT_CHART_JS_VERSION.to_string());
+        let (default_width, default_height) = self.default_size();
+        self.width.get_or_insert(default_width);
+        self.height.get_or_insert(default_height);
+        self.device_pixel_ratio.get_or_insert(DEFAULT_DEVICE_PIXEL_RATIO);
+        self
+    }
+
+    /// Draw legend entries with each dataset's point style instead of a filled box. Sets
+    /// `usePointStyle` in the legend labels config (`options.legend.labels` for Chart.js 2,
+    /// `options.plugins.legend.labels` for 3+), keeping its other settings. Has no effect on
+    /// JS-notation configs.
+    pub fn legend_point_style(mut self, point_style: bool) -> Self {
+        self.options.legend_point_style = Some(point_style);
+        self
+    }
+
+    /// Width in pixels of the colored box next to each legend label. Sets `boxWidth` in the
+    /// legend labels config like [`legend_point_style()`](QuickchartClient::legend_point_style).
+    /// Has no effect on JS-notation configs.
+    pub fn legend_box_width(mut self, width: u32) -> Self {
+        self.options.legend_box_width = Some(width);
+        self
+    }
+
+    /// Whether lines are drawn across `null` data points (`true`) or broken into gaps (`false`,
+    /// Chart.js's default). Sets `options.spanGaps`, which applies to every line dataset that
+    /// doesn't set `spanGaps` itself, e.g. with [`Dataset::span_gaps()`](crate::Dataset::span_gaps).
+    /// Has no effect on JS-notation configs.
+    pub fn span_gaps(mut self, span_gaps: bool) -> Self {
+        self.options.span_gaps = Some(span_gaps);
+        self
+    }
+
+    /// Draw a label on each data point using chartjs-plugin-datalabels, which QuickChart bundles.
+    /// The options are merged into `options.plugins.datalabels`. Has no effect on JS-notation
+    /// configs.
+    pub fn data_labels(mut self, data_labels: DataLabels) -> Self {
+        self.options.data_labels = Some(data_labels);
+        self
+    }
+
+    /// Format y-axis tick labels, and data labels that don't have their own formatter, with
+    /// `Intl.NumberFormat`, e.g. for thousands separators or currency. Data labels are formatted
+    /// when they are enabled through [`data_labels()`](QuickchartClient::data_labels) or the
+    /// config's `options.plugins.datalabels`. No extra plugin is needed.
+    ///
+    /// The formatter is a JavaScript callback, so the config is sent as JS notation. Has no
+    /// effect on JS-notation configs.
+    pub fn number_format(mut self, number_format: NumberFormat) -> Self {
+        self.options.number_format = Some(number_format);
+        self
+    }
+
+    /// Color the datasets with a named palette from chartjs-plugin-colorschemes, which QuickChart
+    /// bundles. The name is merged into `options.plugins.colorschemes.scheme`, and datasets that
+    /// set their own colors keep them.
+    ///
+    /// Names take the form `<family>.<scheme>`, for example `"brewer.Paired12"`,
+    /// `"brewer.SetOne9"`, `"office.Office2016"`, `"tableau.Tableau10"` or
+    /// `"tableau.ClassicColorBlind10"`; see the plugin's documentation for the full list. Names
+    /// aren't validated, and an unknown one leaves the default colors. The plugin only supports
+    /// Chart.js 2, QuickChart's default version. Has no effect on JS-notation configs.
+    pub fn color_scheme(mut self, scheme: String) -> Self {
+        self.options.color_scheme = Some(scheme);
+        self
+    }
+
+    /// Replace the chart with a sparkline of `data`: a thin line chart with no axes, legend,
+    /// tooltips or point markers, for embedding inline next to text. It renders at 100x30
+    /// unless a width or height is set, before or after. Setting another chart with
+    /// [`chart()`](QuickchartClient::chart) or [`chart_value()`](QuickchartClient::chart_value)
+    /// drops the sparkline styling and size.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use quickchart_rs::QuickchartClient;
+    ///
+    /// let client = QuickchartClient::new().sparkline(vec![3.0, 5.0, 2.0, 8.0]);
+    /// assert!(client.get_url().unwrap().ends_with("&w=100&h=30"));
+    /// ```
+    pub fn sparkline(self, data: Vec<f64>) -> Self {
+        let labels: Vec<usize> = (0..data.len()).collect();
+        let chart = serde_json::json!({
+            "type": "line",
+            "data": {
+                "labels": labels,
+                "datasets": [{ "data": data, "fill": false, "borderWidth": 1 }],
+            },
+        });
+        let mut client = self.chart_value(chart);
+        client.options.sparkline = true;
+        client
+    }
+
+    /// Show `text` as the chart title, setting `display` and `text` in `options.title` for
+    /// Chart.js 2 or `options.plugins.title` for Chart.js 3 and later. Other title settings in
+    /// the config, such as its font or position, are kept. Has no effect on JS-notation configs.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use quickchart_rs::QuickchartClient;
+    ///
+    /// let client = QuickchartClient::new()
+    ///     .chart(r#"{"type":"line","data":{"labels":["Jan","Feb"],"datasets":[{"data":[3,5]}]}}"#.to_string())
+    ///     .title("Monthly signups".to_string())
+    ///     .x_axis_label("Month".to_string())
+    ///     .y_axis_label("Signups".to_string());
+    /// ```
+    pub fn title(mut self, text: String) -> Self {
+        self.options.title = Some(text);
+        self
+    }
+
+    /// Label the x axis with `text`, setting the axis' `scaleLabel` (Chart.js 2) or `title`
+    /// (Chart.js 3 and later) like [`title()`](QuickchartClient::title). Has no effect on
+    /// JS-notation configs.
+    pub fn x_axis_label(mut self, text: String) -> Self {
+        self.options.x_axis_label = Some(text);
+        self
+    }
+
+    /// Label the y axis with `text`, like [`x_axis_label()`](QuickchartClient::x_axis_label).
+    pub fn y_axis_label(mut self, text: String) -> Self {
+        self.options.y_axis_label = Some(text);
+        self
+    }
+
+    /// Add a line of text, such as a data-source attribution, along the bottom of the chart.
+    ///
+    /// For Chart.js 3 and later this is the built-in subtitle, placed at the bottom by merging
+    /// `{display: true, text, position: "bottom"}` into `options.plugins.subtitle` (so a subtitle
+    /// already in the config is replaced). Chart.js 2 has no subtitle, so there an inline plugin
+    /// draws the text in small grey type at the bottom of the canvas and 20px of bottom layout
+    /// padding is added to make room; the plugin is JavaScript, so the config is then sent as JS
+    /// notation. Has no effect on JS-notation configs.
+    pub fn footer(mut self, text: String) -> Self {
+        self.options.footer = Some(text);
+        self
+    }
+
+    /// Fill just the plot area, inside the axes, with `color`, leaving the rest of the canvas to
+    /// [`background_color()`](QuickchartClient::background_color). Accepts any CSS color.
+    ///
+    /// Chart.js has no option for this, so an inline plugin that paints the chart area before
+    /// each draw is appended to the config's top-level `plugins` array. The plugin is JavaScript,
+    /// so the config is sent as JS notation. Has no effect on JS-notation configs.
+    pub fn plot_background(mut self, color: String) -> Self {
+        self.options.plot_background = Some(color);
+        self
+    }
+
+    /// Split a single series into pages of `per_chart` points, returning one client per page.
+    ///
+    /// Each client is a copy of this one, so dimensions, background, format and the rest of the
+    /// chart config (type, dataset styling, options) carry over. Only `data.labels` and the first
+    /// dataset's `data` are replaced by the page's points; any other datasets are dropped. The
+    /// last page holds the remainder. Without a chart config, pages start from `{"type":"bar"}`.
+    ///
+    /// Returns [`QCError::InvalidData`] if `per_chart` is 0, and [`QCError::InvalidConfig`] for
+    /// JS-notation or non-object configs, which can't be rewritten.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use quickchart_rs::QuickchartClient;
+    ///
+    /// let data = (1..=120).map(|i| (format!("Day {}", i), i as f64)).collect();
+    /// let pages = QuickchartClient::new()
+    ///     .chart(r#"{"type":"line","data":{"datasets":[{"label":"Requests"}]}}"#.to_string())
+    ///     .width(800)
+    ///     .paginate(data, 50)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(pages.len(), 3);
+    /// ```
+    pub fn paginate(
+        &self,
+        data: Vec<(String, f64)>,
+        per_chart: usize,
+    ) -> Result<Vec<QuickchartClient>, QCError> {
+        if per_chart == 0 {
+            return Err(QCError::InvalidData("per_chart must be greater than 0".to_string()));
+        }
+
+        let base = match self.chart_source_json() {
+            Some(chart) if chart.is_object() => chart,
+            None if self.chart_json.is_none() && self.chart.trim().is_empty() => {
+                serde_json::json!({ "type": "bar" })
+            }
+            _ => {
+                return Err(QCError::InvalidConfig(
+                    "paginate() requires a JSON object config".to_string(),
+                ))
+            }
+        };
+        let base_dataset = base
+            .pointer("/data/datasets/0")
+            .filter(|dataset| dataset.is_object())
+            .cloned()
+            .unwrap_or_else(|| serde_json::json!({}));
+
+        Ok(data
+            .chunks(per_chart)
+            .map(|page| {
+                let (labels, values): (Vec<String>, Vec<f64>) = page.iter().cloned().unzip();
+                let mut dataset = base_dataset.clone();
+                dataset["data"] = serde_json::json!(values);
+
+                let mut chart = base.clone();
+                merge_at(&mut chart, &["data", "labels"], serde_json::json!(labels));
+                merge_at(&mut chart, &["data", "datasets"], serde_json::json!([dataset]));
+
+                self.clone().chart(chart.to_string())
+            })
+            .collect())
+    }
+
+    /// The chart config with builder option overrides merged in. JS-notation configs are
+    /// returned unchanged since they can't be parsed. Overrides containing [`RawJs`](crate::RawJs)
+    /// turn the result into JS-notation.
+    fn resolved_chart(&self) -> String {
+        match self.chart_source_json() {
+            Some(mut chart) if chart.is_object() => {
+                self.options.apply(&mut chart, self.version.as_deref());
+                raw_js::to_config_string(&chart)
+            }
+            Some(chart) => chart.to_string(),
+            None => self.chart.clone(),
+        }
+    }
+
+    /// Fail early, before building a URL or sending a request, if no chart config has been set,
+    /// the device pixel ratio, scale or aspect ratio is out of range, a custom header is
+    /// invalid, or [`json_only()`](QuickchartClient::json_only) is set and the config that would
+    /// be sent isn't strict JSON.
+    fn check_config(&self) -> Result<(), QCError> {
+        let no_chart = self.chart_json.is_none() && self.chart.trim().is_empty();
+        if no_chart && !self.options.builds_chart() {
+            return Err(QCError::EmptyChart);
+        }
+        if let Some(dpr) = self.device_pixel_ratio.filter(|dpr| !dpr.is_finite() || *dpr <= 0.0) {
+            return Err(QCError::InvalidDevicePixelRatio(dpr));
+        }
+        if let Some(scale) = self.scale.filter(|scale| !scale.is_finite() || *scale <= 0.0) {
+            return Err(QCError::InvalidScale(scale));
+        }
+        if let Some(ratio) = self.invalid_aspect_ratio {
+            return Err(QCError::InvalidAspectRatio(ratio));
+        }
+        self.custom_headers()?;
+        let is_json = || serde_json::from_str::<serde_json::Value>(&self.resolved_chart()).is_ok();
+        if self.json_only && !is_json() {
+            return Err(QCError::InvalidConfig(
+                "json_only() is set but the config is not strict JSON".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// The output format sent to QuickChart: the explicit format, falling back to the default.
+    pub(crate) fn resolved_format(&self) -> Option<&str> {
+        self.format.as_deref().or(self.default_format.as_deref())
+    }
+
+    /// The config as set by the user, as JSON: the value edited through
+    /// [`config_mut()`](QuickchartClient::config_mut) if any, otherwise the chart string if it
+    /// parses as JSON.
+    fn chart_source_json(&self) -> Option<serde_json::Value> {
+        match self.chart_json {
+            Some(ref chart_json) => Some(chart_json.clone()),
+            None if self.chart.trim().is_empty() && self.options.builds_chart() => {
+                Some(serde_json::json!({}))
+            }
+            None => serde_json::from_str(&self.chart).ok(),
+        }
+    }
+
+    /// [`resolved_chart()`](QuickchartClient::resolved_chart) as the value sent in a POST body,
+    /// without a serialize/parse round trip for JSON configs. JS-notation configs become a JSON
+    /// string, which QuickChart evaluates.
+    fn resolved_chart_value(&self) -> serde_json::Value {
+        match self.chart_source_json() {
+            Some(mut chart) if chart.is_object() => {
+                self.options.apply(&mut chart, self.version.as_deref());
+                if raw_js::contains_raw_js(&chart) {
+                    serde_json::Value::String(raw_js::to_config_string(&chart))
+                } else {
+                    chart
+                }
+            }
+            Some(chart) => chart,
+            None => serde_json::Value::String(self.chart.clone()),
+        }
+    }
+
+    pub(crate) fn build_json_body(&self) -> serde_json::Value {
+        let chart_value = self.resolved_chart_value();
+        let mut json_body = serde_json::json!({ "chart": chart_value });
+
+        let (width, height) = self.resolved_size();
+        if let Some(w) = width {
+            json_body["width"] = serde_json::Value::Number(w.into());
+        }
+        if let Some(h) = height {
+            json_body["height"] = serde_json::Value::Number(h.into());
+        }
+        // Non-finite ratios can't be represented; check_config() rejects them before sending
+        if let Some(dpr) = self
+            .device_pixel_ratio
+            .and_then(|dpr| serde_json::Number::from_f64(dpr as f64))
+        {
+            json_body["devicePixelRatio"] = serde_json::Value::Number(dpr);
+        }
+        if let Some(ref bkg) = self.background_color {
+            json_body["backgroundColor"] = serde_json::Value::String(bkg.clone());
+        }
+        if let Some(ref v) = self.version {
+            json_body["version"] = serde_json::Value::String(v.clone());
+        }
+        if let Some(f) = self.resolved_format() {
+            json_body["format"] = serde_json::Value::String(f.to_string());
+        }
+        if let Some(ref key) = self.api_key {
+            json_body["key"] = serde_json::Value::String(key.clone());
+        }
+        for (key, value) in self.extra_params.iter().filter(|(key, _)| key != "chart") {
+            json_body[key.as_str()] = serde_json::Value::String(value.clone());
+        }
+
+        json_body
+    }
+
+    /// The chart config exactly as sent in [`get_url()`](QuickchartClient::get_url)'s `c`
+    /// parameter, before percent-encoding (and before base64 with
+    /// [`base64_chart()`](QuickchartClient::base64_chart)), for debugging how a chart renders:
+    /// builder options merged in, then minified. JSON configs are re-serialized without
+    /// whitespace; JS-notation configs have runs of whitespace collapsed to one space.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use quickchart_rs::QuickchartClient;
+    ///
+    /// let client = QuickchartClient::new().chart("{ type: 'bar',\n  data: {} }".to_string());
+    /// assert_eq!(client.compacted_chart(), "{ type: 'bar', data: {} }");
+    /// ```
+    pub fn compacted_chart(&self) -> String {
+        Self::compact_chart(&self.resolved_chart())
+    }
+
+    fn compact_chart(chart: &str) -> String {
+        // Try to parse as JSON
+        if let Ok(chart_json) = serde_json::from_str::<serde_json::Value>(chart) {
+            return serde_json::to_string(&chart_json).unwrap_or_else(|_| chart.to_string());
+        }
+
+        // For non-JSON
+        chart
+            .chars()
+            .fold((String::with_capacity(chart.len()), false), |(mut acc, prev_space), ch| {
+                let is_whitespace = ch.is_whitespace();
+                if is_whitespace && !prev_space {
+                    acc.push(' ');
+                } else if !is_whitespace {
+                    acc.push(ch);
+                }
+                (acc, is_whitespace)
+            })
+            .0
+            .trim()
+            .to_string()
+    }
+
+    /// Check that the chart config is well formed before sending it, catching mistakes such as
+    /// a missing brace locally instead of as a QuickChart error.
+    ///
+    /// Configs that parse as JSON must be objects. Anything else is treated as JS notation and
+    /// checked leniently, since it may contain functions or other code that only a JavaScript
+    /// engine can evaluate: it must start with `{`, close every string and comment, balance its
+    /// brackets, braces and parentheses, and have nothing after the outermost object. Invalid
+    /// expressions or misspelt option names inside a structurally sound config aren't caught;
+    /// see [`validate_type()`](QuickchartClient::validate_type) for the chart type.
+    ///
+    /// Returns [`QCError::EmptyChart`] if no config is set and [`QCError::InvalidChart`],
+    /// describing the first problem found, if it's malformed. Configs set with
+    /// [`chart_value()`](QuickchartClient::chart_value) or edited through
+    /// [`config_mut()`](QuickchartClient::config_mut) are already parsed, so only need to be an
+    /// object.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use quickchart_rs::{QCError, QuickchartClient};
+    ///
+    /// let ok = QuickchartClient::new().chart("{type: 'bar', options: {}}".to_string());
+    /// assert!(ok.validate().is_ok());
+    ///
+    /// let missing_brace = QuickchartClient::new().chart("{type: 'bar', options: {}".to_string());
+    /// assert!(matches!(missing_brace.validate(), Err(QCError::InvalidChart(_))));
+    /// ```
+    pub fn validate(&self) -> Result<(), QCError> {
+        let not_object = || QCError::InvalidChart("the config is not a JSON object".to_string());
+        if let Some(ref chart_json) = self.chart_json {
+            return chart_json.is_object().then_some(()).ok_or_else(not_object);
+        }
+        if self.chart.trim().is_empty() {
+            return Err(QCError::EmptyChart);
+        }
+        match serde_json::from_str::<serde_json::Value>(&self.chart) {
+            Ok(chart) if chart.is_object() => Ok(()),
+            Ok(_) => Err(not_object()),
+            Err(_) => js_object::check_structure(&self.chart).map_err(QCError::InvalidChart),
+        }
+    }
+
+    /// Check the config's `type` against the chart types Chart.js and QuickChart's bundled
+    /// plugins support, catching typos like `"bra"` before a request is made.
+    ///
+    /// Validation is opt-in: nothing calls this automatically, so configs using other plugin or
+    /// custom types can still be sent. Returns [`QCError::UnknownChartType`] for an unrecognized
+    /// type and [`QCError::InvalidConfig`] if a JSON config has no string `type`. A type set with
+    /// [`chart_type()`](QuickchartClient::chart_type) is checked in place of the config's, since
+    /// it's the one sent. JS-notation configs can't be inspected and always pass.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use quickchart_rs::{QCError, QuickchartClient};
+    ///
+    /// let client = QuickchartClient::new().chart(r#"{"type":"bra"}"#.to_string());
+    /// assert!(matches!(client.validate_type(), Err(QCError::UnknownChartType(_))));
+    /// ```
+    pub fn validate_type(&self) -> Result<(), QCError> {
+        let Some(chart) = self.chart_source_json() else {
+            return Ok(());
+        };
+        let chart_type = match self.options.chart_type {
+            Some(ref chart_type) => chart_type.as_str(),
+            None => chart.get("type").and_then(|chart_type| chart_type.as_str()).ok_or_else(|| {
+                QCError::InvalidConfig("missing string \"type\" field".to_string())
+            })?,
+        };
+
+        if KNOWN_CHART_TYPES.contains(&chart_type) {
+            Ok(())
+        } else {
+            Err(QCError::UnknownChartType(chart_type.to_string()))
+        }
+    }
+
+    /// The chart config as set with [`chart()`](QuickchartClient::chart), or serialized from the
+    /// value set with [`chart_value()`](QuickchartClient::chart_value) or edited through
+    /// [`config_mut()`](QuickchartClient::config_mut). Builder option helpers aren't merged in;
+    /// see [`compacted_chart()`](QuickchartClient::compacted_chart) for the config as sent.
+    pub fn get_chart(&self) -> Cow<'_, str> {
+        match self.chart_json {
+            Some(ref chart_json) => Cow::Owned(chart_json.to_string()),
+            None => Cow::Borrowed(&self.chart),
+        }
+    }
+
+    pub fn get_width(&self) -> Option<usize> {
+        self.width
+    }
+
+    pub fn get_height(&self) -> Option<usize> {
+        self.height
+    }
+
+    pub fn get_device_pixel_ratio(&self) -> Option<f32> {
+        self.device_pixel_ratio
+    }
+
+    pub fn get_scale(&self) -> Option<f32> {
+        self.scale
+    }
+
+    pub fn get_background_color(&self) -> Option<&str> {
+        self.background_color.as_deref()
+    }
+
+    pub fn get_version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    pub fn get_api_key(&self) -> Option<&str> {
+        self.api_key.as_deref()
+    }
+
+    pub fn get_extra_params(&self) -> &[(String, String)] {
+        &self.extra_params
+    }
+
+    pub fn get_headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+
+    /// The format set with [`format()`](QuickchartClient::format), not including any
+    /// [`default_format()`](QuickchartClient::default_format).
+    pub fn get_format(&self) -> Option<&str> {
+        self.format.as_deref()
+    }
+
+    pub fn get_default_format(&self) -> Option<&str> {
+        self.default_format.as_deref()
+    }
+
+    pub fn get_url_escape(&self) -> UrlEscape {
+        self.url_escape
+    }
+
+    pub fn get_space_encoding(&self) -> SpaceEncoding {
+        self.space_encoding
+    }
+
+    pub fn get_base64_chart(&self) -> bool {
+        self.base64_chart
+    }
+
+    pub fn get_json_only(&self) -> bool {
+        self.json_only
+    }
+
+    pub fn get_base_url(&self) -> &str {
+        self.base_url.as_str()
+    }
+
+    pub fn get_retries(&self) -> u32 {
+        self.retries
+    }
+
+    pub fn get_retry_base_delay(&self) -> Duration {
+        self.retry_base_delay
+    }
+
+    pub fn get_max_response_bytes(&self) -> Option<usize> {
+        self.max_response_bytes
+    }
+
+    /// Whether two clients are configured the same, e.g. to compare builder output in tests.
+    /// Compares every setting, including host, retry and chart options, but not the HTTP clients
+    /// themselves: a client from [`with_client()`](QuickchartClient::with_client) and root
+    /// certificates added with
+    /// [`add_root_certificate()`](QuickchartClient::add_root_certificate) are ignored.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use quickchart_rs::QuickchartClient;
+    ///
+    /// let base = QuickchartClient::new().chart(r#"{"type":"bar"}"#.to_string());
+    /// assert!(base.clone().width(800).config_eq(&base.clone().width(800)));
+    /// assert!(!base.clone().width(800).config_eq(&base.width(600)));
+    /// ```
+    pub fn config_eq(&self, other: &Self) -> bool {
+        self.http_settings.http1_only == other.http_settings.http1_only
+            && self.http_settings.compression_disabled == other.http_settings.compression_disabled
+            && self.http_settings.timeout == other.http_settings.timeout
+            && self.base_url == other.base_url
+            && self.fallback_base_urls == other.fallback_base_urls
+            && self.fallback_image == other.fallback_image
+            && self.retries == other.retries
+            && self.retry_base_delay == other.retry_base_delay
+            && self.max_response_bytes == other.max_response_bytes
+            && self.chart == other.chart
+            && self.chart_json == other.chart_json
+            && self.width == other.width
+            && self.height == other.height
+            && self.device_pixel_ratio == other.device_pixel_ratio
+            && self.scale == other.scale
+            && self.invalid_aspect_ratio == other.invalid_aspect_ratio
+            && self.background_color == other.background_color
+            && self.version == other.version
+            && self.api_key == other.api_key
+            && self.extra_params == other.extra_params
+            && self.headers == other.headers
+            && self.format == other.format
+            && self.default_format == other.default_format
+            && self.url_escape == other.url_escape
+            && self.space_encoding == other.space_encoding
+            && self.base64_chart == other.base64_chart
+            && self.json_only == other.json_only
+            && self.options == other.options
+    }
+
+    pub fn get_fallback_base_urls(&self) -> Vec<&str> {
+        self.fallback_base_urls.iter().map(Url::as_str).collect()
+    }
+
+    pub fn get_fallback_image(&self) -> Option<&[u8]> {
+        self.fallback_image.as_deref()
+    }
+
+    /// Summarize the request this client would send, for logging or assertions before any
+    /// network call is made.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use quickchart_rs::QuickchartClient;
+    ///
+    /// let summary = QuickchartClient::new()
+    ///     .chart(r#"{"type":"bar"}"#.to_string())
+    ///     .width(800)
+    ///     .describe();
+    ///
+    /// assert_eq!(summary.width, Some(800));
+    /// assert!(summary.is_json);
+    /// ```
+    pub fn describe(&self) -> RenderSummary {
+        let resolved_chart = self.resolved_chart();
+        let endpoint = self
+            .base_url
+            .join(CHART_ENDPOINT)
+            .map(|url| url.to_string())
+            .unwrap_or_else(|_| self.base_url.to_string());
+
+        let (width, height) = self.resolved_size();
+        RenderSummary {
+            endpoint,
+            width,
+            height,
+            device_pixel_ratio: self.device_pixel_ratio,
+            format: self.resolved_format().map(str::to_string),
+            background_color: self.background_color.clone(),
+            config_bytes: Self::compact_chart(&resolved_chart).len(),
+            is_json: serde_json::from_str::<serde_json::Value>(&resolved_chart).is_ok(),
+            estimated_url_length: self.get_url().map(|url| url.len()).ok(),
+        }
+    }
+
+    /// A rough, consistent estimate of how expensive the chart is to render, for routing heavy
+    /// charts to bigger workers or setting thresholds. Higher means more work; the absolute value
+    /// has no unit.
+    ///
+    /// The score is computed from the config as it would be sent, builder overrides included:
+    ///
+    /// ```text
+    /// score = 1
+    ///       + 10 * datasets
+    ///       +  1 * data points (summed over all datasets)
+    ///       + 25 * plugins (entries under options.plugins other than Chart.js's built-in
+    ///                       legend, title, subtitle, tooltip, filler and decimation, plus
+    ///                       entries of the top-level plugins array)
+    /// ```
+    ///
+    /// JS-notation configs are scored if they can be read as plain literals; configs with
+    /// functions or other expressions score the base value of 1.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use quickchart_rs::QuickchartClient;
+    ///
+    /// let client = QuickchartClient::new()
+    ///     .chart(r#"{"type":"bar","data":{"datasets":[{"data":[1,2,3]}]}}"#.to_string());
+    ///
+    /// assert_eq!(client.complexity_score(), 1 + 10 + 3);
+    /// ```
+    pub fn complexity_score(&self) -> u32 {
+        let resolved_chart = self.resolved_chart();
+        let Ok(chart) = serde_json::from_str(&resolved_chart)
+            .or_else(|_| js_object::parse(&resolved_chart))
+        else {
+            return 1;
+        };
+
+        let datasets = chart
+            .pointer("/data/datasets")
+            .and_then(serde_json::Value::as_array)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let data_points: usize = datasets
+            .iter()
+            .filter_map(|dataset| dataset.get("data").and_then(serde_json::Value::as_array))
+            .map(Vec::len)
+            .sum();
+        let option_plugins = chart
+            .pointer("/options/plugins")
+            .and_then(serde_json::Value::as_object)
+            .map(|plugins| {
+                plugins
+                    .keys()
+                    .filter(|name| !BUILT_IN_PLUGINS.contains(&name.as_str()))
+                    .count()
+            })
+            .unwrap_or(0);
+        let inline_plugins = chart
+            .get("plugins")
+            .and_then(serde_json::Value::as_array)
+            .map(Vec::len)
+            .unwrap_or(0);
+
+        let score = 1 + 10 * datasets.len() + data_points + 25 * (option_plugins + inline_plugins);
+        u32::try_from(score).unwrap_or(u32::MAX)
+    }
+
+    /// Generate a chart URL with all configured parameters as query parameters.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use quickchart_rs::QuickchartClient;
+    ///
+    /// let client = QuickchartClient::new()
+    ///     .chart(r#"{"type":"bar","data":{"labels":["A","B"],"datasets":[{"data":[1,2]}]}}"#.to_string())
+    ///     .width(800)
+    ///     .height(400);
+    ///
+    /// let url = client.get_url().unwrap();
+    /// assert!(url.starts_with("https://quickchart.io/chart"));
+    /// assert!(url.contains("w=800"));
+    /// assert!(url.contains("h=400"));
+    /// ```
+    pub fn get_url(&self) -> Result<String, QCError> {
+        Ok(self.get_url_parsed()?.to_string())
+    }
+
+    /// Build the chart URL like [`get_url()`](QuickchartClient::get_url), returned as a parsed
+    /// [`Url`] for callers that add a fragment or inspect the query without re-parsing it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use quickchart_rs::QuickchartClient;
+    ///
+    /// let mut url = QuickchartClient::new()
+    ///     .chart(r#"{"type":"bar"}"#.to_string())
+    ///     .width(800)
+    ///     .get_url_parsed()
+    ///     .unwrap();
+    /// url.set_fragment(Some("preview"));
+    ///
+    /// assert!(url.query_pairs().any(|(key, value)| key == "w" && value == "800"));
+    /// ```
+    pub fn get_url_parsed(&self) -> Result<Url, QCError> {
+        self.check_config()?;
+        let mut url = self.base_url.join(CHART_ENDPOINT)?;
+        let query = self
+            .query_pairs()
+            .iter()
+            .map(|(key, value)| {
+                let escape = if key == "c" { self.url_escape } else { UrlEscape::Form };
+                format!("{}={}", UrlEscape::Form.encode(key), escape.encode(value))
+            })
+            .collect::<Vec<_>>()
+            .join("&");
+        // Form encoding writes a literal `+` as `%2B`, so every `+` left is a space
+        let query = match self.space_encoding {
+            SpaceEncoding::Plus => query,
+            SpaceEncoding::Percent => query.replace('+', "%20"),
+        };
+        url.set_query(Some(&query));
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            url = if self.api_key.is_some() { "<redacted: contains API key>" } else { url.as_str() },
+            url_bytes = url.as_str().len(),
+            "built chart URL"
+        );
+        Ok(url)
+    }
+
+    /// Build the chart URL and report whether it's short enough to use as a GET request, as a
+    /// pre-flight check before choosing between the URL and a POST or short URL.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use quickchart_rs::QuickchartClient;
+    ///
+    /// let report = QuickchartClient::new()
+    ///     .chart(r#"{"type":"bar","data":{"datasets":[{"data":[1,2]}]}}"#.to_string())
+    ///     .check_url()
+    ///     .unwrap();
+    ///
+    /// assert!(report.within_get_limit);
+    /// ```
+    pub fn check_url(&self) -> Result<UrlReport, QCError> {
+        let url = self.get_url()?;
+        let byte_length = url.len();
+        Ok(UrlReport {
+            url,
+            byte_length,
+            within_get_limit: byte_length <= MAX_GET_URL_LENGTH,
+        })
+    }
+
+    /// Build the chart URL and report whether it's longer than `limit` bytes, to decide between
+    /// [`get_url()`](QuickchartClient::get_url) and
+    /// [`get_short_url()`](QuickchartClient::get_short_url) or [`post()`](QuickchartClient::post).
+    /// [`DEFAULT_URL_LENGTH_LIMIT`] is a safe choice for URLs shared with browsers.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use quickchart_rs::{QuickchartClient, DEFAULT_URL_LENGTH_LIMIT};
+    ///
+    /// let client = QuickchartClient::new()
+    ///     .chart(r#"{"type":"bar","data":{"datasets":[{"data":[1,2]}]}}"#.to_string());
+    ///
+    /// assert!(!client.is_url_too_long(DEFAULT_URL_LENGTH_LIMIT).unwrap());
+    /// assert!(client.is_url_too_long(20).unwrap());
+    /// ```
+    pub fn is_url_too_long(&self, limit: usize) -> Result<bool, QCError> {
+        Ok(self.get_url()?.len() > limit)
+    }
+
+    /// Build a standalone HTML page showing the chart (loaded from
+    /// [`get_url()`](QuickchartClient::get_url)) above the config that produced it, for quick
+    /// previews while tweaking a chart. JSON configs are pretty-printed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use quickchart_rs::QuickchartClient;
+    ///
+    /// let html = QuickchartClient::new()
+    ///     .chart(r#"{"type":"bar","data":{"labels":["A","B"],"datasets":[{"data":[1,2]}]}}"#.to_string())
+    ///     .to_preview_html()
+    ///     .unwrap();
+    /// std::fs::write("preview.html", html).unwrap();
+    /// ```
+    pub fn to_preview_html(&self) -> Result<String, QCError> {
+        let url = self.get_url()?;
+        let resolved_chart = self.resolved_chart();
+        let config = serde_json::from_str::<serde_json::Value>(&resolved_chart)
+            .ok()
+            .and_then(|chart| serde_json::to_string_pretty(&chart).ok())
+            .unwrap_or(resolved_chart);
+
+        Ok(format!(
+            r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Chart preview</title>
+</head>
+<body>
+<h1>Chart preview</h1>
+<img src="{}" alt="Chart preview">
+<pre>{}</pre>
+</body>
+</html>
+"#,
+            escape_html(&url),
+            escape_html(&config)
+        ))
+    }
+
+    /// The pieces [`get_url()`](QuickchartClient::get_url) assembles: the chart endpoint URL and
+    /// the query parameters as un-encoded `(key, value)` pairs, for callers that build and encode
+    /// their own links.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use quickchart_rs::QuickchartClient;
+    ///
+    /// let (endpoint, params) = QuickchartClient::new()
+    ///     .chart("{type: 'bar'}".to_string())
+    ///     .width(800)
+    ///     .url_parts()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(endpoint, "https://quickchart.io/chart");
+    /// assert_eq!(params[0], ("c".to_string(), "{type: 'bar'}".to_string()));
+    /// assert_eq!(params[1], ("w".to_string(), "800".to_string()));
+    /// ```
+    pub fn url_parts(&self) -> Result<(String, Vec<(String, String)>), QCError> {
+        self.check_config()?;
+        let url = self.base_url.join(CHART_ENDPOINT)?;
+        Ok((url.to_string(), self.query_pairs()))
+    }
+
+    fn query_pairs(&self) -> Vec<(String, String)> {
+        let chart = self.compacted_chart();
+        let mut pairs = if self.base64_chart {
+            vec![
+                ("c".to_string(), base64::engine::general_purpose::STANDARD.encode(chart)),
+                ("encoding".to_string(), "base64".to_string()),
+            ]
+        } else {
+            vec![("c".to_string(), chart)]
+        };
+        pairs.extend(self.render_pairs());
+        pairs
+    }
+
+    /// The query parameters for everything but the chart config itself.
+    fn render_pairs(&self) -> Vec<(String, String)> {
+        let mut pairs = Vec::new();
+
+        let (width, height) = self.resolved_size();
+        if let Some(w) = width {
+            pairs.push(("w".to_string(), w.to_string()));
+        }
+        if let Some(h) = height {
+            pairs.push(("h".to_string(), h.to_string()));
+        }
+        if let Some(dpr) = self.device_pixel_ratio {
+            pairs.push(("devicePixelRatio".to_string(), dpr.to_string()));
+        }
+        if let Some(ref bkg) = self.background_color {
+            pairs.push(("bkg".to_string(), bkg.clone()));
+        }
+        if let Some(ref v) = self.version {
+            pairs.push(("v".to_string(), v.clone()));
+        }
+        if let Some(f) = self.resolved_format() {
+            pairs.push(("f".to_string(), f.to_string()));
+        }
+        if let Some(ref key) = self.api_key {
+            pairs.push(("key".to_string(), key.clone()));
+        }
+        for (key, value) in self.extra_params.iter().filter(|(key, _)| key != "c") {
+            pairs.retain(|(existing, _)| existing != key);
+            pairs.push((key.clone(), value.clone()));
+        }
+
+        pairs
+    }
+
+    /// Create a short URL for the chart via POST request to `/chart/create`.
+    ///
+    /// The request body is the same as [`post()`](QuickchartClient::post) sends, so the short
+    /// URL renders with the configured width, height, device pixel ratio, background color,
+    /// Chart.js version and format. QuickChart's hosted service has no expiry setting for short
+    /// URLs.
+    ///
+    /// A response body that isn't JSON, such as an HTML error page from a proxy in front of the
+    /// service, fails with [`QCError::JsonParseError`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn run() -> Result<(), quickchart_rs::QCError> {
+    /// use quickchart_rs::QuickchartClient;
+    ///
+    /// let client = QuickchartClient::new()
+    ///     .chart(r#"{"type":"bar","data":{"labels":["A","B"],"datasets":[{"data":[1,2]}]}}"#.to_string())
+    ///     .get_short_url().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub async fn get_short_url(&self) -> Result<String, QCError> {
+        Ok(self.get_short_url_response().await?.url)
+    }
+
+    /// Create a short URL like [`get_short_url()`](QuickchartClient::get_short_url), returning
+    /// the whole parsed response rather than just the URL.
+    pub async fn get_short_url_response(&self) -> Result<ShortUrlResponse, QCError> {
+        let json_body = self.build_json_body();
+        let response = self
+            .send_post_request(CREATE_ENDPOINT, &json_body)
+            .await?;
+
+        let mut body = Vec::new();
+        self.read_body(response, &mut body).await?;
+        self.parse_short_url_response(&body)
+    }
+
+    /// Parse a `/chart/create` response body. A response with an `id` but no `url` gets the
+    /// `/chart/render/<id>` URL on this client's host.
+    fn parse_short_url_response(&self, body: &[u8]) -> Result<ShortUrlResponse, QCError> {
+        let mut response_json: serde_json::Value = serde_json::from_slice(body)?;
+        let field = |name| response_json.get(name).and_then(serde_json::Value::as_str);
+        if field("url").is_none() {
+            let url = match (field("id"), field("error").or(field("message")), field("imageUrl")) {
+                (Some(id), _, _) => {
+                    let mut url = self.base_url.join(TEMPLATE_ENDPOINT)?;
+                    url.path_segments_mut()
+                        .map_err(|_| url::ParseError::RelativeUrlWithCannotBeABaseBase)?
+                        .push(id);
+                    url.to_string()
+                }
+                (None, Some(message), _) => {
+                    return Err(QCError::NoShortUrl(format!("QuickChart reported {:?}", message)))
+                }
+                (None, None, Some(image_url)) => {
+                    return Err(QCError::NoShortUrl(format!("only an image URL, {}", image_url)))
+                }
+                (None, None, None) => return Err(QCError::MissingField("url".to_string())),
+            };
+            response_json["url"] = serde_json::Value::String(url);
+        }
+
+        let mut response: ShortUrlResponse = serde_json::from_value(response_json)?;
+        response.url = response.url.trim_matches('"').trim_matches('\'').to_string();
+        Ok(response)
+    }
+
+    /// Create a short URL for the chart and a PNG QR code that links to it, e.g. for printed
+    /// material. The QR code is rendered by QuickChart's `/qr` endpoint on the primary host.
+    ///
+    /// If creating the short URL fails, its error is returned and no QR code is requested.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn run() -> Result<(), quickchart_rs::QCError> {
+    /// use quickchart_rs::QuickchartClient;
+    ///
+    /// let (short_url, qr_png) = QuickchartClient::new()
+    ///     .chart(r#"{"type":"bar","data":{"labels":["A","B"],"datasets":[{"data":[1,2]}]}}"#.to_string())
+    ///     .short_url_with_qr()
+    ///     .await?;
+    /// std::fs::write("chart-qr.png", qr_png)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn short_url_with_qr(&self) -> Result<(String, Vec<u8>), QCError> {
+        let short_url = self.get_short_url().await?;
+        let qr_code = self
+            .qr_post(&QrCode::new(short_url.clone()).format("png".to_string()))
+            .await?;
+        Ok((short_url, qr_code))
+    }
+
+    /// The URL of a QR code image on this client's QuickChart host. Chart settings such as
+    /// dimensions and format don't apply; configure the QR code on `qr` instead.
+    pub fn qr_url(&self, qr: &QrCode) -> Result<String, QCError> {
+        let mut url = self.base_url.join(QR_ENDPOINT)?;
+        url.query_pairs_mut().extend_pairs(qr.query_pairs());
+        Ok(url.to_string())
+    }
+
+    /// Download a QR code image as bytes from this client's QuickChart host, using its HTTP
+    /// settings. QuickChart serves QR codes over GET, so this fetches
+    /// [`qr_url()`](QuickchartClient::qr_url); fallback hosts aren't used.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn run() -> Result<(), quickchart_rs::QCError> {
+    /// use quickchart_rs::{QrCode, QuickchartClient};
+    ///
+    /// let qr = QrCode::new("https://example.com".to_string()).format("svg".to_string());
+    /// let svg = QuickchartClient::new().qr_post(&qr).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn qr_post(&self, qr: &QrCode) -> Result<Vec<u8>, QCError> {
+        let url = self.qr_url(qr)?;
+        let response = self.check_status(self.client.get(url).send().await?).await?;
+        Ok(response.bytes().await?.to_vec())
+    }
+
+    /// The URL of a Graphviz diagram image on this client's QuickChart host. Chart settings such
+    /// as dimensions and format don't apply; configure the diagram on `graph` instead.
+    pub fn graphviz_url(&self, graph: &Graphviz) -> Result<String, QCError> {
+        let mut url = self.base_url.join(GRAPHVIZ_ENDPOINT)?;
+        url.query_pairs_mut().extend_pairs(graph.query_pairs());
+        Ok(url.to_string())
+    }
+
+    /// Download a Graphviz diagram image as bytes from this client's QuickChart host, using its
+    /// HTTP settings. This fetches [`graphviz_url()`](QuickchartClient::graphviz_url) over GET;
+    /// fallback hosts aren't used.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn run() -> Result<(), quickchart_rs::QCError> {
+    /// use quickchart_rs::{Graphviz, QuickchartClient};
+    ///
+    /// let graph = Graphviz::new("digraph { a -> b; b -> c }".to_string());
+    /// let svg = QuickchartClient::new().graphviz_post(&graph).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn graphviz_post(&self, graph: &Graphviz) -> Result<Vec<u8>, QCError> {
+        let url = self.graphviz_url(graph)?;
+        let response = self.check_status(self.client.get(url).send().await?).await?;
+        Ok(response.bytes().await?.to_vec())
+    }
+
+    /// The URL rendering a saved chart template on this client's QuickChart host, with the
+    /// template's parameters followed by this client's dimensions, device pixel ratio,
+    /// background, format and API key. The client's own chart config isn't used.
+    pub fn template_url(&self, template: &ChartTemplate) -> Result<String, QCError> {
+        let mut url = self.base_url.join(TEMPLATE_ENDPOINT)?;
+        url.path_segments_mut()
+            .map_err(|_| url::ParseError::RelativeUrlWithCannotBeABaseBase)?
+            .push(template.get_id());
+        url.query_pairs_mut()
+            .extend_pairs(template.get_params())
+            .extend_pairs(self.render_pairs());
+        Ok(url.to_string())
+    }
+
+    /// Download a saved chart template rendered with its parameters as bytes, using this client's
+    /// HTTP settings. Templates are rendered over GET, so this fetches
+    /// [`template_url()`](QuickchartClient::template_url); fallback hosts aren't used.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn run() -> Result<(), quickchart_rs::QCError> {
+    /// use quickchart_rs::{ChartTemplate, QuickchartClient};
+    ///
+    /// let template = ChartTemplate::new("zf-abc-123".to_string())
+    ///     .param("labels".to_string(), "Mon,Tue,Wed".to_string());
+    /// let png = QuickchartClient::new().render_template(&template).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn render_template(&self, template: &ChartTemplate) -> Result<Vec<u8>, QCError> {
+        let url = self.template_url(template)?;
+        let response = self.check_status(self.client.get(url).send().await?).await?;
+        Ok(response.bytes().await?.to_vec())
+    }
+
+    /// Create a short URL tuned for chat link previews in Slack or Discord.
+    ///
+    /// The chart is rendered at 800x420, close to the 1.91:1 ratio chat platforms use for image
+    /// previews, on a white background so it reads well in both light and dark themes. These
+    /// override any dimensions or background set on this client. A short URL is returned because
+    /// chat platforms cache unfurls by URL, so the link stays stable once posted.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use quickchart_rs::QuickchartClient;
+    /// # async fn run() -> Result<(), quickchart_rs::QCError> {
+    /// let link = QuickchartClient::new()
+    ///     .chart(r#"{"type":"bar","data":{"labels":["A","B"],"datasets":[{"data":[1,2]}]}}"#.to_string())
+    ///     .chat_unfurl()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn chat_unfurl(&self) -> Result<String, QCError> {
+        self.clone()
+            .width(CHAT_UNFURL_WIDTH)
+            .height(CHAT_UNFURL_HEIGHT)
+            .background_color("white".to_string())
+            .get_short_url()
+            .await
+    }
+
+    /// Build the request [`post()`](QuickchartClient::post) would send to `endpoint` on the
+    /// primary host, e.g. `"chart"` or `"chart/create"`, without sending it: the URL, headers and
+    /// JSON body are all set. Useful for inspecting exactly what goes over the wire, or for
+    /// sending it through your own middleware with
+    /// [`reqwest::Client::execute`](reqwest::Client::execute).
+    ///
+    /// Retries and [`fallback_base_urls()`](QuickchartClient::fallback_base_urls) only apply to
+    /// requests the client sends itself.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use quickchart_rs::QuickchartClient;
+    ///
+    /// let request = QuickchartClient::new()
+    ///     .chart(r#"{"type":"bar"}"#.to_string())
+    ///     .build_request("chart")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(request.method(), "POST");
+    /// assert_eq!(request.url().as_str(), "https://quickchart.io/chart");
+    /// ```
+    pub fn build_request(&self, endpoint: &str) -> Result<reqwest::Request, QCError> {
+        self.check_config()?;
+        let body = serde_json::to_string(&self.build_json_body())?;
+        let url = self.base_url.join(endpoint)?;
+        Ok(self.post_request_builder(url, body)?.build()?)
+    }
+
+    /// A POST request to `url` with the JSON content type and custom headers set.
+    fn post_request_builder(
+        &self,
+        url: Url,
+        body: String,
+    ) -> Result<reqwest::RequestBuilder, QCError> {
+        Ok(self
+            .client
+            .post(url)
+            .header("Content-Type", "application/json")
+            .headers(self.custom_headers()?)
+            .body(body))
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self, json_body)))]
+    async fn send_post_request(
+        &self,
+        endpoint: &str,
+        json_body: &serde_json::Value,
+    ) -> Result<reqwest::Response, QCError> {
+        self.check_config()?;
+        let body = serde_json::to_string(json_body)?;
+
+        let mut attempt = 0;
+        loop {
+            let (result, retry_after) = self.send_to_hosts(endpoint, &body).await;
+            match result {
+                Err(ref e) if attempt < self.retries && Self::should_retry(e) => {
+                    let delay = self.retry_delay(attempt, retry_after);
+                    #[cfg(feature = "tracing")]
+                    tracing::debug!(attempt, ?delay, error = %e, "retrying QuickChart request");
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Send the request to the primary host, failing over to the fallback hosts, along with the
+    /// `Retry-After` delay of the last error response, if any.
+    async fn send_to_hosts(
+        &self,
+        endpoint: &str,
+        body: &str,
+    ) -> (Result<reqwest::Response, QCError>, Option<Duration>) {
+        let mut last_error = None;
+        let mut retry_after = None;
+
+        for base_url in std::iter::once(&self.base_url).chain(&self.fallback_base_urls) {
+            let url = match base_url.join(endpoint) {
+                Ok(url) => url,
+                Err(e) => return (Err(e.into()), None),
+            };
+            let request = match self.post_request_builder(url.clone(), body.to_string()) {
+                Ok(request) => request,
+                Err(e) => return (Err(e), None),
+            };
+            let result = request.send().await;
+            #[cfg(feature = "tracing")]
+            match result {
+                Ok(ref response) => tracing::debug!(
+                    %url,
+                    body_bytes = body.len(),
+                    status = response.status().as_u16(),
+                    "QuickChart responded"
+                ),
+                Err(ref e) => {
+                    tracing::debug!(%url, body_bytes = body.len(), error = %e, "request failed")
+                }
+            }
+            let result = match result {
+                Ok(response) => {
+                    retry_after = Self::retry_after(response.headers());
+                    self.check_status(response).await
+                }
+                Err(e) => {
+                    retry_after = None;
+                    Err(e.into())
+                }
+            };
+
+            match result {
+                Ok(response) => return (Ok(response), None),
+                Err(e) if Self::should_fail_over(&e) => last_error = Some(e),
+                Err(e) => return (Err(e), retry_after),
+            }
+        }
+
+        (Err(last_error.expect("the primary base URL is always tried")), retry_after)
+    }
+
+    /// Turn a 4xx or 5xx response into [`QCError::ApiError`], reading its body for the message.
+    async fn check_status(
+        &self,
+        mut response: reqwest::Response,
+    ) -> Result<reqwest::Response, QCError> {
+        let status = response.status();
+        if !status.is_client_error() && !status.is_server_error() {
+            return Ok(response);
+        }
+        let limit = self.error_body_limit();
+        let mut body = Vec::new();
+        while let Ok(Some(chunk)) = response.chunk().await {
+            let remaining = limit - body.len();
+            body.extend_from_slice(&chunk[..chunk.len().min(remaining)]);
+            if body.len() == limit {
+                break;
+            }
+        }
+        Err(Self::api_error(status.as_u16(), &String::from_utf8_lossy(&body)))
+    }
+
+    /// How many bytes of an error response body to read: the message is truncated rather than
+    /// failing with [`QCError::ResponseTooLarge`], so the status is still reported.
+    pub(crate) fn error_body_limit(&self) -> usize {
+        self.max_response_bytes
+            .map_or(MAX_ERROR_BODY_BYTES, |limit| limit.min(MAX_ERROR_BODY_BYTES))
+    }
+
+    /// Build an [`QCError::ApiError`] from an error response body, preferring the `error` field
+    /// of a QuickChart JSON error (`{"success":false,"error":"..."}`) over the raw text.
+    fn api_error(status: u16, body: &str) -> QCError {
+        let message = serde_json::from_str::<serde_json::Value>(body)
+            .ok()
+            .and_then(|json| json.get("error")?.as_str().map(str::to_string))
+            .unwrap_or_else(|| body.trim().to_string());
+        QCError::ApiError { status, message }
+    }
+
+    /// Whether a failed request is worth repeating against another host: the host couldn't be
+    /// reached or timed out, or returned a 5xx. 4xx errors mean the request itself is bad.
+    fn should_fail_over(error: &QCError) -> bool {
+        match error {
+            QCError::HttpError(e) => e.is_connect() || e.is_timeout(),
+            QCError::ApiError { status, .. } => *status >= 500,
+            _ => false,
+        }
+    }
+
+    /// Whether a failed request is worth sending again after a wait: the host couldn't be
+    /// reached or timed out, is rate limiting, or returned a 5xx.
+    fn should_retry(error: &QCError) -> bool {
+        match error {
+            QCError::ApiError { status, .. } => *status == 429 || *status >= 500,
+            _ => Self::should_fail_over(error),
+        }
+    }
+
+    /// How long to wait before retry number `attempt` (counting from 0): the server's
+    /// `Retry-After` if given, capped at [`MAX_RETRY_AFTER`], otherwise the base delay doubled
+    /// for each earlier attempt.
+    fn retry_delay(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        match retry_after {
+            Some(retry_after) => retry_after.min(MAX_RETRY_AFTER),
+            None => self.retry_base_delay.saturating_mul(2u32.saturating_pow(attempt)),
+        }
+    }
+
+    /// A `Retry-After` header in seconds. The HTTP-date form isn't supported and is ignored.
+    fn retry_after(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+        let seconds = headers.get(reqwest::header::RETRY_AFTER)?.to_str().ok()?;
+        seconds.trim().parse().ok().map(Duration::from_secs)
+    }
+
+    /// Download the chart image as bytes via POST request.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use quickchart_rs::QuickchartClient;
+    /// # async fn run() -> Result<(), quickchart_rs::QCError> {
+    /// let client = QuickchartClient::new()
+    ///     .chart(r#"{"type":"bar","data":{"labels":["A","B"],"datasets":[{"data":[1,2]}]}}"#.to_string())
+    ///     .post().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub async fn post(&self) -> Result<Vec<u8>, QCError> {
+        self.fetch_image(&self.build_json_body())
+            .await
+            .or_else(|e| self.fallback_image_for(&e).cloned().ok_or(e))
+    }
+
+    /// Download the chart image like [`post()`](QuickchartClient::post), giving up with
+    /// [`QCError::Cancelled`] as soon as `cancel` completes, e.g. when the client of a web
+    /// handler disconnects. The in-flight request is dropped, closing its connection, and
+    /// [`fallback_image()`](QuickchartClient::fallback_image) isn't applied. If `cancel` is
+    /// already complete when this is first polled, no request is sent.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn run(disconnected: impl std::future::Future<Output = ()>) {
+    /// use quickchart_rs::{QCError, QuickchartClient};
+    ///
+    /// let client = QuickchartClient::new().chart(r#"{"type":"bar"}"#.to_string());
+    /// match client.post_with_cancel(disconnected).await {
+    ///     Ok(image) => { /* send the image */ }
+    ///     Err(QCError::Cancelled) => { /* nobody is waiting for it any more */ }
+    ///     Err(e) => { /* report the error */ }
+    /// }
+    /// # }
+    /// ```
+    pub async fn post_with_cancel(
+        &self,
+        cancel: impl Future<Output = ()>,
+    ) -> Result<Vec<u8>, QCError> {
+        Self::with_cancel(self.post(), cancel).await
+    }
+
+    /// Create a short URL like [`get_short_url()`](QuickchartClient::get_short_url), giving up
+    /// with [`QCError::Cancelled`] as soon as `cancel` completes, like
+    /// [`post_with_cancel()`](QuickchartClient::post_with_cancel).
+    pub async fn get_short_url_with_cancel(
+        &self,
+        cancel: impl Future<Output = ()>,
+    ) -> Result<String, QCError> {
+        Self::with_cancel(self.get_short_url(), cancel).await
+    }
+
+    /// Run `request` until it finishes or `cancel` completes, checking `cancel` first.
+    async fn with_cancel<T>(
+        request: impl Future<Output = Result<T, QCError>>,
+        cancel: impl Future<Output = ()>,
+    ) -> Result<T, QCError> {
+        tokio::select! {
+            biased;
+            _ = cancel => Err(QCError::Cancelled),
+            result = request => result,
+        }
+    }
+
+    /// Download the chart image like [`post()`](QuickchartClient::post) and return it with the
+    /// exact chart config that was sent, minified, e.g. to store both for an audit trail. JSON
+    /// configs are returned as compact JSON with builder options merged in, JS-notation configs
+    /// as sent.
+    ///
+    /// [`fallback_image()`](QuickchartClient::fallback_image) isn't applied, so the image is
+    /// always the one rendered from the returned config.
+    pub async fn post_with_config(&self) -> Result<(Vec<u8>, String), QCError> {
+        let json_body = self.build_json_body();
+        let config = match json_body["chart"] {
+            serde_json::Value::String(ref config) => config.clone(),
+            ref config => config.to_string(),
+        };
+        let image_bytes = self.fetch_image(&json_body).await?;
+        Ok((image_bytes, config))
+    }
+
+    /// Download the chart image like [`post()`](QuickchartClient::post) and return it with the
+    /// response's `Content-Type` header, e.g. `image/png` or `image/svg+xml`, to pick a file
+    /// extension when no format is pinned. The header is `None` if missing or not valid text.
+    ///
+    /// [`fallback_image()`](QuickchartClient::fallback_image) isn't applied, since the
+    /// placeholder has no content type.
+    pub async fn post_with_content_type(&self) -> Result<(Vec<u8>, Option<String>), QCError> {
+        let response = self
+            .send_post_request(CHART_ENDPOINT, &self.build_json_body())
+            .await?;
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let mut image_bytes = Vec::new();
+        self.read_body(response, &mut image_bytes).await?;
+        Ok((image_bytes, content_type))
+    }
+
+    async fn fetch_image(&self, json_body: &serde_json::Value) -> Result<Vec<u8>, QCError> {
+        let response = self.send_post_request(CHART_ENDPOINT, json_body).await?;
+        let mut image_bytes = Vec::new();
+        self.read_body(response, &mut image_bytes).await?;
+        Ok(image_bytes)
+    }
+
+    /// Append the response body to `buf`, enforcing
+    /// [`max_response_bytes()`](QuickchartClient::max_response_bytes).
+    async fn read_body(
+        &self,
+        mut response: reqwest::Response,
+        buf: &mut Vec<u8>,
+    ) -> Result<(), QCError> {
+        if let Some(len) = response.content_length() {
+            self.check_response_size(len)?;
+        }
+        while let Some(chunk) = response.chunk().await? {
+            self.check_response_size((buf.len() + chunk.len()) as u64)?;
+            buf.extend_from_slice(&chunk);
+        }
+        Ok(())
+    }
+
+    /// Fail with [`QCError::ResponseTooLarge`] if a body of `len` bytes is over the limit.
+    pub(crate) fn check_response_size(&self, len: u64) -> Result<(), QCError> {
+        match self.max_response_bytes {
+            Some(limit) if len > limit as u64 => Err(QCError::ResponseTooLarge { limit }),
+            _ => Ok(()),
+        }
+    }
+
+    /// The configured fallback image, if `error` is one it stands in for.
+    fn fallback_image_for(&self, error: &QCError) -> Option<&Vec<u8>> {
+        match error {
+            QCError::HttpError(e) if e.is_body() => self.fallback_image.as_ref(),
+            e if Self::should_fail_over(e) => self.fallback_image.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Render only the chart's legend, for layouts that place the legend apart from the chart
+    /// (typically rendered itself with the legend hidden).
+    ///
+    /// QuickChart has no legend-only mode, so this renders a copy of the chart with every
+    /// dataset's data removed, both axes hidden and the legend forced on. The legend keeps the
+    /// datasets' labels and colors, and the image keeps this client's dimensions and format, with
+    /// the area below the legend left blank; set a small [`height()`](QuickchartClient::height)
+    /// to crop it. Legends that depend on the data, such as a pie chart's per-slice entries or a
+    /// custom label generator, may be drawn empty. Returns [`QCError::InvalidConfig`] for
+    /// JS-notation configs, which can't be rewritten.
+    pub async fn legend_image(&self) -> Result<Vec<u8>, QCError> {
+        self.legend_client()?.post().await
+    }
+
+    fn legend_client(&self) -> Result<Self, QCError> {
+        if !self.chart_source_json().is_some_and(|chart| chart.is_object()) {
+            return Err(QCError::InvalidConfig(
+                "legend_image() requires a JSON config".to_string(),
+            ));
+        }
+        let mut client = self.clone();
+        client.options.legend_only = true;
+        Ok(client)
+    }
+
+    /// Download the chart image and return it along with its standard base64 encoding, e.g. to
+    /// store the bytes and inline a preview from one render.
+    pub async fn post_with_base64(&self) -> Result<(Vec<u8>, String), QCError> {
+        let image_bytes = self.post().await?;
+        let encoded = base64::engine::general_purpose::STANDARD.encode(&image_bytes);
+        Ok((image_bytes, encoded))
+    }
+
+    /// Download the chart image into `buf`, reusing its allocation instead of returning a new
+    /// `Vec` like [`post()`](QuickchartClient::post). Useful when rendering many charts in a loop.
+    ///
+    /// The buffer is cleared before the request is sent and holds only the image bytes on
+    /// success. On error it is left empty, unless it is an error
+    /// [`fallback_image()`](QuickchartClient::fallback_image) covers, in which case it holds the
+    /// fallback bytes.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use quickchart_rs::QuickchartClient;
+    /// # async fn run() -> Result<(), quickchart_rs::QCError> {
+    /// let mut buf = Vec::new();
+    /// for value in [1, 2, 3] {
+    ///     QuickchartClient::new()
+    ///         .chart(format!(r#"{{"type":"line","data":{{"datasets":[{{"data":[{}]}}]}}}}"#, value))
+    ///         .post_into(&mut buf)
+    ///         .await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn post_into(&self, buf: &mut Vec<u8>) -> Result<(), QCError> {
+        match self.fetch_image_into(buf).await {
+            Err(e) => match self.fallback_image_for(&e) {
+                Some(fallback) => {
+                    buf.extend_from_slice(fallback);
+                    Ok(())
+                }
+                None => Err(e),
+            },
+            result => result,
+        }
+    }
+
+    /// Download the chart image and copy it to `writer` chunk by chunk as it arrives, instead of
+    /// holding the whole image in memory first, and return the number of bytes written. Useful
+    /// for large PDFs or when streaming straight into a socket or archive.
+    ///
+    /// If the request fails with an error [`fallback_image()`](QuickchartClient::fallback_image)
+    /// covers, the fallback bytes are written instead. An error partway through the download is
+    /// returned as-is, with the bytes received so far already written.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use quickchart_rs::QuickchartClient;
+    /// # async fn run() -> Result<(), quickchart_rs::QCError> {
+    /// let file = std::fs::File::create("report.pdf")?;
+    /// let written = QuickchartClient::new()
+    ///     .chart(r#"{"type":"bar","data":{"labels":["A","B"],"datasets":[{"data":[1,2]}]}}"#.to_string())
+    ///     .format("pdf".to_string())
+    ///     .write_to(std::io::BufWriter::new(file))
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn write_to<W: Write>(&self, mut writer: W) -> Result<u64, QCError> {
+        let json_body = self.build_json_body();
+        let mut response = match self.send_post_request(CHART_ENDPOINT, &json_body).await {
+            Ok(response) => response,
+            Err(e) => {
+                let fallback = self.fallback_image_for(&e).ok_or(e)?;
+                Self::write_image(&mut writer, fallback)?;
+                return Ok(fallback.len() as u64);
+            }
+        };
+
+        if let Some(len) = response.content_length() {
+            self.check_response_size(len)?;
+        }
+        let mut written = 0;
+        while let Some(chunk) = response.chunk().await? {
+            self.check_response_size(written + chunk.len() as u64)?;
+            writer.write_all(&chunk)?;
+            written += chunk.len() as u64;
+        }
+        writer.flush()?;
+        Ok(written)
+    }
+
+    async fn fetch_image_into(&self, buf: &mut Vec<u8>) -> Result<(), QCError> {
+        buf.clear();
+        let json_body = self.build_json_body();
+        let response = self.send_post_request(CHART_ENDPOINT, &json_body).await?;
+
+        let result = self.read_body(response, buf).await;
+        if result.is_err() {
+            buf.clear();
+        }
+        result
+    }
+
+    /// Download the chart image like [`post()`](QuickchartClient::post), but fail with
+    /// [`QCError::BlankImage`] if the rendered image is entirely one color.
+    ///
+    /// The check decodes the PNG and compares a 64x64 grid of evenly spaced pixels against the
+    /// top-left pixel, so a chart whose only marks fall between sample points can be reported as
+    /// blank. It only applies to PNG output (the default when no format is set); other formats and
+    /// responses that can't be decoded as PNG are returned unchecked. Requires the `image` feature.
+    #[cfg(feature = "image")]
+    pub async fn post_checked(&self) -> Result<Vec<u8>, QCError> {
+        let image_bytes = self.post().await?;
+        let is_png = self
+            .resolved_format()
+            .is_none_or(|format| format.eq_ignore_ascii_case("png"));
+
+        if is_png && Self::is_blank_image(&image_bytes) {
+            return Err(QCError::BlankImage);
+        }
+        Ok(image_bytes)
+    }
+
+    /// Download the chart image like [`post()`](QuickchartClient::post) and decode it, e.g. to
+    /// composite it into a larger image or resize it. Requires the `image` feature.
+    ///
+    /// Works with PNG output (the default when no format is set) and WebP. SVG and PDF aren't
+    /// raster formats, so for them, and for formats QuickChart may add later, this fails with
+    /// [`QCError::ImageDecode`] before sending a request. A response that doesn't decode in the
+    /// requested format, such as a [`fallback_image()`](QuickchartClient::fallback_image) in
+    /// another one, fails with the same error.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn run() -> Result<(), quickchart_rs::QCError> {
+    /// use quickchart_rs::QuickchartClient;
+    ///
+    /// let chart = QuickchartClient::new()
+    ///     .chart(r#"{"type":"bar","data":{"labels":["A","B"],"datasets":[{"data":[1,2]}]}}"#.to_string())
+    ///     .to_image()
+    ///     .await?;
+    /// let thumbnail = chart.thumbnail(100, 60);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "image")]
+    pub async fn to_image(&self) -> Result<image::DynamicImage, QCError> {
+        let format = match self.resolved_format().map(str::to_ascii_lowercase).as_deref() {
+            None | Some("png") => image::ImageFormat::Png,
+            Some("webp") => image::ImageFormat::WebP,
+            Some(other) => {
+                return Err(QCError::ImageDecode(format!(
+                    "{} output is not a raster image; use png or webp",
+                    other
+                )))
+            }
+        };
+        let image_bytes = self.post().await?;
+        image::load_from_memory_with_format(&image_bytes, format)
+            .map_err(|e| QCError::ImageDecode(e.to_string()))
+    }
+
+    #[cfg(feature = "image")]
+    fn is_blank_image(bytes: &[u8]) -> bool {
+        let Ok(image) = image::load_from_memory_with_format(bytes, image::ImageFormat::Png) else {
+            return false;
+        };
+        let image = image.to_rgba8();
+        let (width, height) = (image.width() as u64, image.height() as u64);
+        if width == 0 || height == 0 {
+            return true;
+        }
+
+        let last = BLANK_CHECK_SAMPLES_PER_AXIS - 1;
+        let first_pixel = image.get_pixel(0, 0);
+        (0..=last).all(|i| {
+            (0..=last).all(|j| {
+                let x = (i * (width - 1) / last) as u32;
+                let y = (j * (height - 1) / last) as u32;
+                image.get_pixel(x, y) == first_pixel
+            })
+        })
+    }
+
+    /// Download the chart image and save it directly to a file. Convenience method that combines
+    /// [`post()`](QuickchartClient::post) and file writing.
+    ///
+    /// If no [`format()`](QuickchartClient::format) is set, a `.png`, `.svg`, `.webp` or `.pdf`
+    /// extension picks the format to render, taking precedence over
+    /// [`default_format()`](QuickchartClient::default_format). An extension naming a different
+    /// format than the one set returns [`QCError::FormatMismatch`] before any request is made.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use quickchart_rs::QuickchartClient;
+    /// # async fn run() -> Result<(), quickchart_rs::QCError> {
+    /// let client = QuickchartClient::new()
+    ///     .chart(r#"{"type":"bar","data":{"labels":["A","B"],"datasets":[{"data":[1,2]}]}}"#.to_string())
+    ///     .to_file("output.png")
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(path = %path.as_ref().display()))
+    )]
+    pub async fn to_file(&self, path: impl AsRef<Path>) -> Result<(), QCError> {
+        let image_bytes = self.for_path(path.as_ref())?.post().await?;
+        std::fs::write(path, image_bytes)?;
+        Ok(())
+    }
+
+    /// The client to render `path` with: this one with the format its extension names filled in
+    /// if none is set. Extensions that aren't image formats leave the client unchanged.
+    fn for_path(&self, path: &Path) -> Result<Cow<'_, QuickchartClient>, QCError> {
+        let Some((extension, inferred)) = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .and_then(|extension| Some((extension, ImageFormat::from_extension(extension)?)))
+        else {
+            return Ok(Cow::Borrowed(self));
+        };
+
+        match self.format {
+            None => Ok(Cow::Owned(self.clone().format_typed(inferred))),
+            Some(ref format) if format.eq_ignore_ascii_case(inferred.as_str()) => {
+                Ok(Cow::Borrowed(self))
+            }
+            Some(ref format) => Err(QCError::FormatMismatch {
+                extension: extension.to_string(),
+                format: format.clone(),
+            }),
+        }
+    }
+
+    /// Download the chart image and write the raw bytes to standard output, for piping into other
+    /// tools (`mytool | convert - chart.png`).
+    ///
+    /// The bytes are written unmodified and stdout is flushed before returning. The download
+    /// completes before stdout is locked, so nothing is written if the request fails.
+    pub async fn to_stdout(&self) -> Result<(), QCError> {
+        let image_bytes = self.post().await?;
+        Self::write_image(&mut std::io::stdout().lock(), &image_bytes)
+    }
+
+    fn write_image(writer: &mut impl Write, image_bytes: &[u8]) -> Result<(), QCError> {
+        writer.write_all(image_bytes)?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
@@ -1,4 +1,12 @@
 use super::*;
+use crate::test_util::{unreachable_url, MockResponse, MockServer};
+use crate::RawJs;
+
+fn mock_client(server: &MockServer) -> QuickchartClient {
+    let mut client = QuickchartClient::new().chart(r#"{"type":"bar"}"#.to_string());
+    client.base_url = Url::parse(server.url()).unwrap();
+    client
+}
 
 #[test]
 fn test_new_client() {
@@ -303,3 +311,2195 @@ fn test_background_color_rgb_hsl_formats() {
     assert!(url::Url::parse(&url).is_ok());
 }
 
+
+#[test]
+fn test_fixed_size() {
+    let client = QuickchartClient::new()
+        .chart(r#"{"type":"bar","options":{"responsive":true,"plugins":{"legend":{"display":false}}}}"#.to_string())
+        .fixed_size();
+
+    let json_body = client.build_json_body();
+    let options = &json_body["chart"]["options"];
+    assert_eq!(options["responsive"], false);
+    assert_eq!(options["maintainAspectRatio"], false);
+    // Existing options are preserved
+    assert_eq!(options["plugins"]["legend"]["display"], false);
+}
+
+#[test]
+fn test_fixed_size_ignores_js_notation() {
+    let chart = "{type: 'bar', options: {responsive: true}}";
+    let client = QuickchartClient::new()
+        .chart(chart.to_string())
+        .fixed_size();
+
+    assert_eq!(client.build_json_body()["chart"], chart);
+}
+
+#[test]
+fn test_paginate() {
+    let data: Vec<(String, f64)> = (1..=5).map(|i| (format!("P{}", i), i as f64)).collect();
+    let pages = QuickchartClient::new()
+        .chart(r#"{"type":"line","data":{"labels":["old"],"datasets":[{"label":"Sales","data":[0],"borderColor":"red"},{"data":[9]}]}}"#.to_string())
+        .width(400)
+        .height(200)
+        .background_color("white".to_string())
+        .paginate(data, 2)
+        .unwrap();
+
+    assert_eq!(pages.len(), 3);
+
+    let charts: Vec<serde_json::Value> = pages
+        .iter()
+        .map(|page| serde_json::from_str(&page.chart).unwrap())
+        .collect();
+    assert_eq!(charts[0]["data"]["labels"], serde_json::json!(["P1", "P2"]));
+    assert_eq!(charts[1]["data"]["labels"], serde_json::json!(["P3", "P4"]));
+    // Remainder page
+    assert_eq!(charts[2]["data"]["labels"], serde_json::json!(["P5"]));
+    assert_eq!(charts[2]["data"]["datasets"][0]["data"], serde_json::json!([5.0]));
+
+    for (page, chart) in pages.iter().zip(&charts) {
+        assert_eq!(page.width, Some(400));
+        assert_eq!(page.height, Some(200));
+        assert_eq!(page.background_color, Some("white".to_string()));
+        assert_eq!(chart["type"], "line");
+        assert_eq!(chart["data"]["datasets"].as_array().unwrap().len(), 1);
+        assert_eq!(chart["data"]["datasets"][0]["label"], "Sales");
+        assert_eq!(chart["data"]["datasets"][0]["borderColor"], "red");
+    }
+}
+
+#[test]
+fn test_paginate_errors() {
+    let data = vec![("A".to_string(), 1.0), ("B".to_string(), 2.0)];
+    let client = QuickchartClient::new().chart(r#"{"type":"line"}"#.to_string());
+    assert!(matches!(client.paginate(data.clone(), 0), Err(QCError::InvalidData(_))));
+
+    let js = QuickchartClient::new().chart("{type: 'line', data: {datasets: [{}]}}".to_string());
+    assert!(matches!(js.paginate(data.clone(), 1), Err(QCError::InvalidConfig(_))));
+
+    // No config at all starts from a bar chart
+    let pages = QuickchartClient::new().paginate(data, 1).unwrap();
+    assert_eq!(pages.len(), 2);
+    assert_eq!(serde_json::from_str::<serde_json::Value>(&pages[0].chart).unwrap()["type"], "bar");
+}
+
+#[cfg(feature = "image")]
+fn encode_png(image: image::RgbaImage) -> Vec<u8> {
+    let mut bytes = std::io::Cursor::new(Vec::new());
+    image.write_to(&mut bytes, image::ImageFormat::Png).unwrap();
+    bytes.into_inner()
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn test_is_blank_image() {
+    let blank = image::RgbaImage::from_pixel(500, 300, image::Rgba([255, 255, 255, 255]));
+    assert!(QuickchartClient::is_blank_image(&encode_png(blank)));
+
+    let mut chart = image::RgbaImage::from_pixel(500, 300, image::Rgba([255, 255, 255, 255]));
+    for x in 100..200 {
+        for y in 50..300 {
+            chart.put_pixel(x, y, image::Rgba([54, 162, 235, 255]));
+        }
+    }
+    assert!(!QuickchartClient::is_blank_image(&encode_png(chart)));
+
+    // Undecodable bytes are not reported as blank
+    assert!(!QuickchartClient::is_blank_image(b"<svg></svg>"));
+}
+
+#[cfg(feature = "image")]
+#[tokio::test]
+async fn test_to_image() {
+    let pixel = image::Rgba([54, 162, 235, 255]);
+    let png = encode_png(image::RgbaImage::from_pixel(4, 3, pixel));
+    let server = MockServer::start(vec![
+        MockResponse::new(200, png),
+        MockResponse::new(200, "not an image"),
+    ]);
+    let client = mock_client(&server);
+
+    let decoded = client.to_image().await.unwrap().to_rgba8();
+    assert_eq!(decoded.dimensions(), (4, 3));
+    assert_eq!(*decoded.get_pixel(3, 2), pixel);
+
+    assert!(matches!(client.to_image().await, Err(QCError::ImageDecode(_))));
+
+    for format in ["svg", "PDF"] {
+        let result = client.clone().format(format.to_string()).to_image().await;
+        assert!(matches!(result, Err(QCError::ImageDecode(ref message)) if message.contains("raster")));
+    }
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[test]
+fn test_data_labels() {
+    let client = QuickchartClient::new()
+        .chart(r#"{"type":"bar","options":{"plugins":{"datalabels":{"font":{"size":14}}}}}"#.to_string())
+        .data_labels(
+            DataLabels::new()
+                .align("top".to_string())
+                .anchor("end".to_string())
+                .color("#333".to_string()),
+        );
+
+    let json_body = client.build_json_body();
+    let datalabels = &json_body["chart"]["options"]["plugins"]["datalabels"];
+    assert_eq!(datalabels["display"], true);
+    assert_eq!(datalabels["align"], "top");
+    assert_eq!(datalabels["anchor"], "end");
+    assert_eq!(datalabels["color"], "#333");
+    assert_eq!(datalabels["font"]["size"], 14);
+}
+
+#[test]
+fn test_data_labels_formatter_emits_js() {
+    let client = QuickchartClient::new()
+        .chart(r#"{"type":"bar"}"#.to_string())
+        .data_labels(DataLabels::new().formatter(RawJs::new("(value) => value + '%'".to_string())));
+
+    // A function can't be represented in JSON, so the chart is sent as JS-notation
+    let json_body = client.build_json_body();
+    let chart = json_body["chart"].as_str().unwrap();
+    assert!(chart.contains(r#""formatter":(value) => value + '%'"#));
+    assert!(chart.contains(r#""display":true"#));
+}
+
+#[test]
+fn test_animation_disabled_by_default() {
+    let client = QuickchartClient::new()
+        .chart(r#"{"type":"bar","options":{"animation":{"duration":1000}}}"#.to_string());
+
+    assert_eq!(client.build_json_body()["chart"]["options"]["animation"], false);
+    assert!(client.get_url().unwrap().contains("animation"));
+}
+
+#[test]
+fn test_keep_animation() {
+    let client = QuickchartClient::new()
+        .chart(r#"{"type":"bar","options":{"animation":{"duration":1000}}}"#.to_string())
+        .keep_animation();
+
+    assert_eq!(client.build_json_body()["chart"]["options"]["animation"]["duration"], 1000);
+
+    let client = QuickchartClient::new()
+        .chart(r#"{"type":"bar"}"#.to_string())
+        .keep_animation();
+
+    assert!(client.build_json_body()["chart"].get("options").is_none());
+}
+
+#[tokio::test]
+async fn test_post_into_reuses_buffer() {
+    let server = MockServer::start(vec![MockResponse::new(200, b"\x89PNG fake image".to_vec())]);
+    let client = mock_client(&server);
+
+    let mut buf = b"stale bytes from a previous render".to_vec();
+    client.post_into(&mut buf).await.unwrap();
+
+    assert_eq!(buf, b"\x89PNG fake image");
+
+    let request = &server.requests()[0];
+    assert_eq!(request.method, "POST");
+    assert_eq!(request.path, "/chart");
+    assert_eq!(request.header("content-type"), Some("application/json"));
+    assert_eq!(request.json()["chart"]["type"], "bar");
+}
+
+#[tokio::test]
+async fn test_with_client() {
+    let server = MockServer::start(vec![MockResponse::new(200, "image")]);
+    let http = reqwest::Client::builder().user_agent("custom-agent/1.0").build().unwrap();
+    let client = QuickchartClient::with_client(http)
+        .base_url(server.url())
+        .unwrap()
+        .chart(r#"{"type":"bar"}"#.to_string())
+        .timeout(Duration::from_secs(5));
+
+    assert_eq!(client.post().await.unwrap(), b"image");
+
+    // Connection settings don't replace the supplied client
+    let request = &server.requests()[0];
+    assert_eq!(request.header("user-agent"), Some("custom-agent/1.0"));
+    assert_eq!(request.json()["chart"]["type"], "bar");
+}
+
+#[tokio::test]
+async fn test_api_key() {
+    let server = MockServer::start(vec![MockResponse::new(200, "image")]);
+    let client = mock_client(&server).api_key("q4 key/+&".to_string());
+
+    let url = client.get_url().unwrap();
+    assert!(url.ends_with("&key=q4+key%2F%2B%26"));
+    let url = Url::parse(&url).unwrap();
+    assert!(url.query_pairs().any(|(key, value)| key == "key" && value == "q4 key/+&"));
+
+    client.post().await.unwrap();
+    assert_eq!(server.requests()[0].json()["key"], "q4 key/+&");
+    assert!(!format!("{:?}", client).contains("q4 key"));
+}
+
+#[tokio::test]
+async fn test_post_into_clears_buffer_on_error() {
+    let server = MockServer::start(vec![MockResponse::new(500, "boom")]);
+    let client = mock_client(&server);
+
+    let mut buf = b"stale".to_vec();
+    assert!(client.post_into(&mut buf).await.is_err());
+    assert!(buf.is_empty());
+}
+
+#[test]
+fn test_grid_style_chart_js_3() {
+    let client = QuickchartClient::new()
+        .chart(r#"{"type":"line","options":{"scales":{"y":{"beginAtZero":true}}}}"#.to_string())
+        .version("3".to_string())
+        .grid_color("#eeeeee".to_string())
+        .grid_dash(vec![4, 2]);
+
+    let scales = &client.build_json_body()["chart"]["options"]["scales"];
+    for axis in ["x", "y"] {
+        assert_eq!(scales[axis]["grid"]["color"], "#eeeeee");
+        assert_eq!(scales[axis]["grid"]["borderDash"], serde_json::json!([4, 2]));
+    }
+    assert_eq!(scales["y"]["beginAtZero"], true);
+}
+
+#[test]
+fn test_grid_style_chart_js_4_uses_border_dash() {
+    let client = QuickchartClient::new()
+        .chart(r#"{"type":"line"}"#.to_string())
+        .version("4".to_string())
+        .grid_dash(vec![3, 3]);
+
+    let scales = &client.build_json_body()["chart"]["options"]["scales"];
+    for axis in ["x", "y"] {
+        assert_eq!(scales[axis]["border"]["dash"], serde_json::json!([3, 3]));
+    }
+}
+
+#[test]
+fn test_grid_style_chart_js_2() {
+    // QuickChart renders with Chart.js 2 unless a version is set
+    let client = QuickchartClient::new()
+        .chart(r#"{"type":"line","options":{"scales":{"yAxes":[{"id":"left"},{"id":"right"}]}}}"#.to_string())
+        .grid_color("#eeeeee".to_string())
+        .grid_dash(vec![4, 2]);
+
+    let scales = &client.build_json_body()["chart"]["options"]["scales"];
+    assert_eq!(scales["xAxes"][0]["gridLines"]["color"], "#eeeeee");
+    for axis in scales["yAxes"].as_array().unwrap() {
+        assert_eq!(axis["gridLines"]["color"], "#eeeeee");
+        assert_eq!(axis["gridLines"]["borderDash"], serde_json::json!([4, 2]));
+    }
+    assert_eq!(scales["yAxes"][1]["id"], "right");
+}
+
+#[test]
+fn test_url_parts() {
+    let client = QuickchartClient::new()
+        .chart(r#"{"type":"bar","data":{"labels":["Test & Value"]}}"#.to_string())
+        .width(800)
+        .height(400)
+        .keep_animation();
+
+    let (endpoint, pairs) = client.url_parts().unwrap();
+    assert_eq!(endpoint, "https://quickchart.io/chart");
+    let (_, chart) = pairs.iter().find(|(key, _)| key == "c").unwrap();
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(chart).unwrap(),
+        serde_json::json!({"type": "bar", "data": {"labels": ["Test & Value"]}})
+    );
+    assert!(pairs.contains(&("w".to_string(), "800".to_string())));
+    assert!(pairs.contains(&("h".to_string(), "400".to_string())));
+
+    // Reassembling the pairs gives the same URL as get_url
+    let mut url = Url::parse(&endpoint).unwrap();
+    url.query_pairs_mut().extend_pairs(&pairs);
+    assert_eq!(url.to_string(), client.get_url().unwrap());
+}
+
+#[tokio::test]
+async fn test_get_short_url_response() {
+    let server = MockServer::start(vec![
+        MockResponse::new(200, r#"{"success":true,"url":"https://quickchart.io/chart/render/a"}"#),
+        MockResponse::new(
+            200,
+            r#"{"url":"https://quickchart.io/chart/render/b","imageUrl":"https://quickchart.io/b.png","extra":1}"#,
+        ),
+        MockResponse::new(200, r#"{"success":true,"id":"zf-c"}"#),
+        MockResponse::new(200, r#"{"success":false,"error":"Rate limited"}"#),
+        MockResponse::new(200, r#"{"imageUrl":"https://quickchart.io/d.png"}"#),
+        MockResponse::new(200, r#"{"success":false}"#),
+    ]);
+    let client = mock_client(&server);
+
+    let response = client.get_short_url_response().await.unwrap();
+    assert_eq!(
+        response,
+        ShortUrlResponse {
+            success: true,
+            url: "https://quickchart.io/chart/render/a".to_string(),
+            image_url: None,
+            extra: serde_json::Map::new(),
+        }
+    );
+
+    // Other fields are kept and a missing success flag reads as false
+    let response = client.get_short_url_response().await.unwrap();
+    assert!(!response.success);
+    assert_eq!(response.url, "https://quickchart.io/chart/render/b");
+    assert_eq!(response.image_url.as_deref(), Some("https://quickchart.io/b.png"));
+    assert_eq!(response.extra["extra"], 1);
+
+    // An id without a url is turned into a render URL on the client's host
+    let url = client.get_short_url().await.unwrap();
+    assert_eq!(url, format!("{}/chart/render/zf-c", server.url()));
+
+    let error = client.get_short_url().await;
+    assert!(matches!(error, Err(QCError::NoShortUrl(ref message)) if message.contains("Rate limited")));
+    let image_only = client.get_short_url().await;
+    assert!(matches!(image_only, Err(QCError::NoShortUrl(ref message)) if message.contains("d.png")));
+
+    let missing = client.get_short_url().await;
+    assert!(matches!(missing, Err(QCError::MissingField(ref field)) if field == "url"));
+}
+
+#[tokio::test]
+async fn test_get_short_url_sends_all_settings() {
+    let server = MockServer::start(vec![MockResponse::new(
+        200,
+        r#"{"success":true,"url":"https://quickchart.io/chart/render/sf-1"}"#,
+    )]);
+    let client = mock_client(&server)
+        .width(640)
+        .height(360)
+        .device_pixel_ratio(1.5)
+        .background_color("#fafafa".to_string())
+        .version("4".to_string())
+        .format("svg".to_string())
+        .api_key("secret".to_string());
+
+    client.get_short_url().await.unwrap();
+
+    let request = &server.requests()[0];
+    assert_eq!(request.method, "POST");
+    assert_eq!(request.path, "/chart/create");
+    let body = request.json();
+    assert_eq!(body["chart"]["type"], "bar");
+    assert_eq!(body["width"], 640);
+    assert_eq!(body["height"], 360);
+    assert_eq!(body["devicePixelRatio"], 1.5);
+    assert_eq!(body["backgroundColor"], "#fafafa");
+    assert_eq!(body["version"], "4");
+    assert_eq!(body["format"], "svg");
+    assert_eq!(body["key"], "secret");
+    assert_eq!(body, client.build_json_body());
+}
+
+#[tokio::test]
+async fn test_chat_unfurl() {
+    let server = MockServer::start(vec![MockResponse::new(
+        200,
+        r#"{"success":true,"url":"https://quickchart.io/chart/render/abc123"}"#,
+    )]);
+    let client = mock_client(&server).width(300).background_color("black".to_string());
+
+    let url = client.chat_unfurl().await.unwrap();
+    assert_eq!(url, "https://quickchart.io/chart/render/abc123");
+
+    let request = &server.requests()[0];
+    assert_eq!(request.path, "/chart/create");
+    let body = request.json();
+    assert_eq!(body["width"], 800);
+    assert_eq!(body["height"], 420);
+    assert_eq!(body["backgroundColor"], "white");
+}
+
+const TEST_CA_PEM: &str = "-----BEGIN CERTIFICATE-----
+MIIBlzCCAT2gAwIBAgIUMFKECFwDgceKmBlILe53c35ZVeIwCgYIKoZIzj0EAwIw
+IDEeMBwGA1UEAwwVcXVpY2tjaGFydC1ycyB0ZXN0IENBMCAXDTI2MTAxNDE1MzU0
+N1oYDzIxMjYwOTIwMTUzNTQ3WjAgMR4wHAYDVQQDDBVxdWlja2NoYXJ0LXJzIHRl
+c3QgQ0EwWTATBgcqhkjOPQIBBggqhkjOPQMBBwNCAARbAYLb+mOx5FFbTPe8MAic
+X+zs4Gul+Ejt4HPoy95FPJygUEb/4beuf/1Hp3hHWGSyMvJpOOD6BbbZhcDWjRh1
+o1MwUTAdBgNVHQ4EFgQU82kMjdy762a9dkrm9LCjfgQ0adIwHwYDVR0jBBgwFoAU
+82kMjdy762a9dkrm9LCjfgQ0adIwDwYDVR0TAQH/BAUwAwEB/zAKBggqhkjOPQQD
+AgNIADBFAiEA7rVSxsXKb+i+8FImDxkwOBNfahrrYbM+4R8jmJVVh9UCIB8QqZms
+C6qwRafF63UW/8MG0Y3lizrqcAaquFK5AFI/
+-----END CERTIFICATE-----
+";
+
+#[test]
+fn test_add_root_certificate() {
+    let certificate = Certificate::from_pem(TEST_CA_PEM.as_bytes()).unwrap();
+    let client = QuickchartClient::new()
+        .add_root_certificate(certificate)
+        .chart(r#"{"type":"bar"}"#.to_string());
+
+    assert_eq!(client.http_settings.root_certificates.len(), 1);
+    assert!(client.get_url().is_ok());
+}
+
+#[test]
+fn test_url_escape_policies() {
+    let chart = "{type: 'bar', data: {labels: ['A B']}}";
+
+    let form_url = QuickchartClient::new().chart(chart.to_string()).get_url().unwrap();
+    assert!(form_url.contains("c=%7Btype%3A+%27bar%27%2C+data%3A+%7Blabels%3A+%5B%27A+B%27%5D%7D%7D"));
+
+    let strict_url = QuickchartClient::new()
+        .chart(chart.to_string())
+        .url_escape(UrlEscape::NonAlphanumeric)
+        .width(800)
+        .get_url()
+        .unwrap();
+    assert!(strict_url.contains(
+        "c=%7Btype%3A%20%27bar%27%2C%20data%3A%20%7Blabels%3A%20%5B%27A%20B%27%5D%7D%7D&w=800"
+    ));
+
+    // Both decode back to the same config
+    for url in [form_url, strict_url] {
+        let url = Url::parse(&url).unwrap();
+        let (_, c) = url.query_pairs().find(|(key, _)| key == "c").unwrap();
+        assert_eq!(c, chart);
+    }
+}
+
+#[test]
+fn test_base64_chart_round_trip() {
+    let chart = r#"{"type":"line","data":{"labels":["Jan","Feb"],"datasets":[{"data":[1,2]}]}}"#;
+    let client = QuickchartClient::new()
+        .chart(chart.to_string())
+        .base64_chart(true)
+        .width(500);
+
+    let url = Url::parse(&client.get_url().unwrap()).unwrap();
+    let pairs: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+    assert_eq!(pairs[1], ("encoding".to_string(), "base64".to_string()));
+    assert_eq!(pairs[2], ("w".to_string(), "500".to_string()));
+
+    let decoded = base64::engine::general_purpose::STANDARD.decode(&pairs[0].1).unwrap();
+    assert_eq!(String::from_utf8(decoded.clone()).unwrap(), client.compacted_chart());
+    let decoded: serde_json::Value = serde_json::from_slice(&decoded).unwrap();
+    assert_eq!(decoded["type"], "line");
+    assert_eq!(decoded["data"], serde_json::from_str::<serde_json::Value>(chart).unwrap()["data"]);
+}
+
+#[tokio::test]
+async fn test_post_with_base64() {
+    let image = b"\x89PNG\r\n\x1a\n fake image".to_vec();
+    let server = MockServer::start(vec![MockResponse::new(200, image.clone())]);
+
+    let (bytes, encoded) = mock_client(&server).post_with_base64().await.unwrap();
+    assert_eq!(bytes, image);
+    assert_eq!(base64::engine::general_purpose::STANDARD.decode(encoded).unwrap(), image);
+}
+
+#[test]
+fn test_rotate_labels() {
+    let client = QuickchartClient::new()
+        .chart(r#"{"type":"bar"}"#.to_string())
+        .version("4".to_string())
+        .rotate_labels(45);
+
+    let options = &client.build_json_body()["chart"]["options"];
+    assert_eq!(options["scales"]["x"]["ticks"]["maxRotation"], 45);
+    assert_eq!(options["scales"]["x"]["ticks"]["minRotation"], 45);
+    assert_eq!(options["layout"]["padding"]["bottom"], 15.0);
+}
+
+#[test]
+fn test_rotate_labels_adds_to_existing_padding() {
+    let client = QuickchartClient::new()
+        .chart(r#"{"type":"bar","options":{"layout":{"padding":10}}}"#.to_string())
+        .rotate_labels(90);
+
+    let options = &client.build_json_body()["chart"]["options"];
+    assert_eq!(options["scales"]["xAxes"][0]["ticks"]["maxRotation"], 90);
+    assert_eq!(options["layout"]["padding"]["top"], 10);
+    assert_eq!(options["layout"]["padding"]["bottom"], 40.0);
+}
+
+#[test]
+fn test_describe() {
+    let client = QuickchartClient::new()
+        .chart(r#"{ "type": "bar" }"#.to_string())
+        .width(800)
+        .height(400)
+        .device_pixel_ratio(2.0)
+        .background_color("white".to_string())
+        .format("png".to_string())
+        .keep_animation();
+
+    let summary = client.describe();
+    assert_eq!(summary.endpoint, "https://quickchart.io/chart");
+    assert_eq!(summary.width, Some(800));
+    assert_eq!(summary.height, Some(400));
+    assert_eq!(summary.device_pixel_ratio, Some(2.0));
+    assert_eq!(summary.format, Some("png".to_string()));
+    assert_eq!(summary.background_color, Some("white".to_string()));
+    assert_eq!(summary.config_bytes, r#"{"type":"bar"}"#.len());
+    assert!(summary.is_json);
+    assert_eq!(summary.estimated_url_length, Some(client.get_url().unwrap().len()));
+
+    let summary = QuickchartClient::new().chart("{type: 'bar'}".to_string()).describe();
+    assert!(!summary.is_json);
+
+    // No URL can be built without a config, or with an invalid setting
+    assert_eq!(QuickchartClient::new().describe().estimated_url_length, None);
+    let invalid = client.clone().device_pixel_ratio(0.0).describe();
+    assert_eq!(invalid.estimated_url_length, None);
+}
+
+#[tokio::test]
+async fn test_fallback_base_urls_on_server_error() {
+    let primary = MockServer::start(vec![MockResponse::new(503, "unavailable")]);
+    let secondary = MockServer::start(vec![MockResponse::new(200, "image")]);
+    let client = mock_client(&primary)
+        .fallback_base_urls(vec![secondary.url().to_string()])
+        .unwrap();
+
+    assert_eq!(client.post().await.unwrap(), b"image");
+    assert_eq!(primary.requests().len(), 1);
+    assert_eq!(secondary.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn test_fallback_base_urls_on_connection_failure() {
+    let secondary = MockServer::start(vec![MockResponse::new(200, "image")]);
+    let mut client = QuickchartClient::new()
+        .chart(r#"{"type":"bar"}"#.to_string())
+        .fallback_base_urls(vec![secondary.url().to_string()])
+        .unwrap();
+    client.base_url = Url::parse(&unreachable_url()).unwrap();
+
+    assert_eq!(client.post().await.unwrap(), b"image");
+}
+
+#[tokio::test]
+async fn test_fallback_base_urls_not_used_for_client_errors() {
+    let primary = MockServer::start(vec![MockResponse::new(400, "bad chart")]);
+    let secondary = MockServer::start(vec![MockResponse::new(200, "image")]);
+    let client = mock_client(&primary)
+        .fallback_base_urls(vec![secondary.url().to_string()])
+        .unwrap();
+
+    let error = client.post().await.unwrap_err();
+    assert!(matches!(error, QCError::ApiError { status: 400, .. }));
+    assert!(secondary.requests().is_empty());
+}
+
+#[tokio::test]
+async fn test_fallback_base_urls_returns_last_error() {
+    let primary = MockServer::start(vec![MockResponse::new(500, "error")]);
+    let secondary = MockServer::start(vec![MockResponse::new(502, "error")]);
+    let client = mock_client(&primary)
+        .fallback_base_urls(vec![secondary.url().to_string()])
+        .unwrap();
+
+    let error = client.post().await.unwrap_err();
+    assert!(matches!(error, QCError::ApiError { status: 502, .. }));
+}
+
+#[test]
+fn test_fallback_base_urls_rejects_invalid_url() {
+    let result = QuickchartClient::new().fallback_base_urls(vec!["not a url".to_string()]);
+    assert!(matches!(result, Err(QCError::UrlParseError(_))));
+}
+
+#[test]
+fn test_validate_type_accepts_known_types() {
+    for chart_type in ["bar", "line", "pie", "doughnut", "radar", "polarArea", "scatter", "bubble", "sankey"] {
+        let client = QuickchartClient::new().chart(format!(r#"{{"type":"{}"}}"#, chart_type));
+        assert!(client.validate_type().is_ok(), "{} should be valid", chart_type);
+    }
+}
+
+#[test]
+fn test_validate_type_rejects_typos() {
+    let client = QuickchartClient::new().chart(r#"{"type":"bra"}"#.to_string());
+    assert!(matches!(client.validate_type(), Err(QCError::UnknownChartType(ref t)) if t == "bra"));
+
+    let client = QuickchartClient::new().chart(r#"{"data":{}}"#.to_string());
+    assert!(matches!(client.validate_type(), Err(QCError::InvalidConfig(_))));
+
+    // JS-notation can't be inspected
+    let client = QuickchartClient::new().chart("{type: 'bra'}".to_string());
+    assert!(client.validate_type().is_ok());
+}
+
+#[test]
+fn test_validate_type_checks_chart_type_override() {
+    let client = QuickchartClient::new()
+        .chart(r#"{"type":"bar"}"#.to_string())
+        .chart_type("bra".to_string());
+    assert!(matches!(client.validate_type(), Err(QCError::UnknownChartType(ref t)) if t == "bra"));
+
+    let client = QuickchartClient::new()
+        .chart(r#"{"data":{}}"#.to_string())
+        .chart_type("line".to_string());
+    assert!(client.validate_type().is_ok());
+}
+
+#[test]
+fn test_deterministic() {
+    let client = QuickchartClient::new()
+        .chart(r#"{"type":"bar","data":{"datasets":[{"data":[1]},{"data":[2],"backgroundColor":"red"}]}}"#.to_string())
+        .keep_animation()
+        .height(250)
+        .deterministic();
+
+    let body = client.build_json_body();
+    assert_eq!(body["version"], "2.9.4");
+    assert_eq!(body["width"], 500);
+    assert_eq!(body["height"], 250);
+    assert_eq!(body["devicePixelRatio"].as_f64().unwrap(), 2.0);
+
+    let chart = &body["chart"];
+    assert_eq!(chart["options"]["animation"], false);
+    assert_eq!(chart["options"]["responsive"], false);
+    let datasets = &chart["data"]["datasets"];
+    assert_eq!(datasets[0]["backgroundColor"], "rgb(54, 162, 235)");
+    assert_eq!(datasets[0]["borderColor"], "rgb(54, 162, 235)");
+    assert_eq!(datasets[1]["backgroundColor"], "red");
+    assert_eq!(datasets[1]["borderColor"], "rgb(255, 99, 132)");
+}
+
+#[test]
+fn test_tick_helpers() {
+    let client = QuickchartClient::new()
+        .chart(r#"{"type":"line","options":{"scales":{"y":{"ticks":{"color":"gray"}}}}}"#.to_string())
+        .version("3".to_string())
+        .y_tick_step(0.5)
+        .y_max_ticks(6)
+        .x_max_ticks(10);
+
+    let scales = &client.build_json_body()["chart"]["options"]["scales"];
+    assert_eq!(scales["y"]["ticks"]["stepSize"], 0.5);
+    assert_eq!(scales["y"]["ticks"]["maxTicksLimit"], 6);
+    assert_eq!(scales["y"]["ticks"]["color"], "gray");
+    assert_eq!(scales["x"]["ticks"]["maxTicksLimit"], 10);
+    assert!(scales["x"]["ticks"].get("stepSize").is_none());
+}
+
+#[test]
+fn test_tick_helpers_chart_js_2() {
+    let client = QuickchartClient::new()
+        .chart(r#"{"type":"line"}"#.to_string())
+        .x_tick_step(5.0)
+        .y_max_ticks(4);
+
+    let scales = &client.build_json_body()["chart"]["options"]["scales"];
+    assert_eq!(scales["xAxes"][0]["ticks"]["stepSize"], 5.0);
+    assert_eq!(scales["yAxes"][0]["ticks"]["maxTicksLimit"], 4);
+    assert!(scales["xAxes"][0]["ticks"].get("maxTicksLimit").is_none());
+}
+
+#[test]
+fn test_to_preview_html() {
+    let client = QuickchartClient::new()
+        .chart(r#"{"type":"bar","data":{"labels":["<b>A & B</b>"]}}"#.to_string())
+        .width(300);
+
+    let html = client.to_preview_html().unwrap();
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<title>Chart preview</title>"));
+    assert!(html.contains(&format!(r#"<img src="{}""#, escape_html(&client.get_url().unwrap()))));
+    assert!(html.contains("&quot;&lt;b&gt;A &amp; B&lt;/b&gt;&quot;"));
+    assert!(!html.contains("<b>A"));
+}
+
+#[tokio::test]
+async fn test_write_image_for_stdout() {
+    let image = b"\x89PNG\r\n\x1a\n\x00\xff binary".to_vec();
+    let server = MockServer::start(vec![MockResponse::new(200, image.clone())]);
+    let image_bytes = mock_client(&server).post().await.unwrap();
+
+    let mut output = std::io::BufWriter::new(Vec::new());
+    QuickchartClient::write_image(&mut output, &image_bytes).unwrap();
+
+    // Flushed, so the inner writer already has every byte
+    assert_eq!(output.get_ref(), &image);
+}
+
+#[test]
+fn test_config_mut() {
+    let mut client = QuickchartClient::new()
+        .chart("{type: 'bar', data: {labels: ['A', 'B'], datasets: [{data: [1, 2]}]}}".to_string())
+        .width(400);
+
+    let config = client.config_mut().unwrap();
+    config["type"] = "line".into();
+    config["data"]["datasets"][0]["label"] = "Sales".into();
+
+    // Re-reading returns the edited value rather than re-parsing the original string
+    assert_eq!(client.config_mut().unwrap()["type"], "line");
+
+    let chart = &client.build_json_body()["chart"];
+    assert_eq!(chart["type"], "line");
+    assert_eq!(chart["data"]["labels"], serde_json::json!(["A", "B"]));
+    assert_eq!(chart["data"]["datasets"][0]["label"], "Sales");
+    assert!(client.get_url().unwrap().contains("Sales"));
+
+    // Setting a new chart string discards the edits
+    let mut client = client.chart(r#"{"type":"pie"}"#.to_string());
+    assert_eq!(client.config_mut().unwrap()["type"], "pie");
+}
+
+#[test]
+fn test_chart_value() {
+    let value = serde_json::json!({
+        "type": "bar",
+        "data": { "labels": ["A"], "datasets": [{ "data": [u64::MAX] }] }
+    });
+    let client = QuickchartClient::new().chart_value(value.clone()).width(300);
+
+    let body = client.build_json_body();
+    assert_eq!(body["chart"]["data"], value["data"]);
+    assert_eq!(body["width"], 300);
+    assert!(client.get_url().unwrap().contains("18446744073709551615"));
+    // The getter reflects the value, serialized
+    assert_eq!(client.get_chart(), value.to_string());
+
+    let mut edited = client.clone();
+    edited.config_mut().unwrap()["type"] = serde_json::json!("line");
+    assert!(edited.get_chart().starts_with(r#"{"type":"line","#));
+
+    // A later chart string replaces the value
+    let client = client.chart(r#"{"type":"pie"}"#.to_string());
+    assert_eq!(client.build_json_body()["chart"]["type"], "pie");
+}
+
+#[test]
+fn test_config_mut_rejects_unparseable_js() {
+    let mut client = QuickchartClient::new()
+        .chart("{type: 'bar', options: {onClick: function() {}}}".to_string());
+
+    assert!(matches!(client.config_mut(), Err(QCError::InvalidConfig(_))));
+}
+
+#[test]
+fn test_default_format_inherited_by_forks() {
+    let base = QuickchartClient::new()
+        .chart(r#"{"type":"bar"}"#.to_string())
+        .default_format("svg".to_string());
+
+    let inherited = base.clone().width(400);
+    assert_eq!(inherited.build_json_body()["format"], "svg");
+    assert!(inherited.get_url().unwrap().contains("f=svg"));
+    assert_eq!(inherited.describe().format.as_deref(), Some("svg"));
+
+    let overridden = base.clone().format("png".to_string());
+    assert_eq!(overridden.build_json_body()["format"], "png");
+    assert!(overridden.get_url().unwrap().contains("f=png"));
+
+    // Setting a default after an explicit format doesn't replace it
+    let explicit = QuickchartClient::new()
+        .format("webp".to_string())
+        .default_format("svg".to_string());
+    assert_eq!(explicit.build_json_body()["format"], "webp");
+
+    assert!(QuickchartClient::new().build_json_body().get("format").is_none());
+}
+
+#[test]
+fn test_complexity_score() {
+    let chart_with_points = |points: usize| {
+        let data: Vec<usize> = (0..points).collect();
+        QuickchartClient::new().chart(
+            serde_json::json!({ "type": "line", "data": { "datasets": [{ "data": data }] } })
+                .to_string(),
+        )
+    };
+
+    let small = chart_with_points(10);
+    let large = chart_with_points(1000);
+    assert_eq!(small.complexity_score(), 1 + 10 + 10);
+    assert!(large.complexity_score() > small.complexity_score());
+
+    // Plugins add weight, built-in ones don't
+    let legend = chart_with_points(10).chart(
+        r#"{"type":"line","data":{"datasets":[{"data":[1]}]},"options":{"plugins":{"legend":{}}}}"#
+            .to_string(),
+    );
+    assert_eq!(legend.complexity_score(), 1 + 10 + 1);
+    let labelled = legend.clone().data_labels(crate::DataLabels::new());
+    assert_eq!(labelled.complexity_score(), 1 + 10 + 1 + 25);
+
+    // JS-notation literals are scored too; configs with functions get the base score
+    let js = QuickchartClient::new()
+        .chart("{type: 'bar', data: {datasets: [{data: [1, 2]}, {data: [3]}]}}".to_string());
+    assert_eq!(js.complexity_score(), 1 + 20 + 3);
+    let with_function = QuickchartClient::new()
+        .chart("{type: 'bar', options: {onClick: function() {}}}".to_string());
+    assert_eq!(with_function.complexity_score(), 1);
+}
+
+#[test]
+fn test_legend_image_config() {
+    let client = QuickchartClient::new()
+        .chart(
+            r#"{"type":"line","data":{"labels":["A","B"],"datasets":[
+                {"label":"Sales","data":[1,2],"borderColor":"red"},
+                {"label":"Costs","data":[3,4]}
+            ]},"options":{"legend":{"display":false}}}"#
+                .to_string(),
+        )
+        .width(300)
+        .height(40);
+
+    let body = client.legend_client().unwrap().build_json_body();
+    let chart = &body["chart"];
+    assert_eq!(
+        chart["data"]["datasets"][0],
+        serde_json::json!({ "label": "Sales", "data": [], "borderColor": "red" })
+    );
+    assert_eq!(chart["data"]["datasets"][1]["data"], serde_json::json!([]));
+    assert_eq!(chart["options"]["legend"]["display"], true);
+    assert_eq!(chart["options"]["tooltips"]["enabled"], false);
+    assert_eq!(chart["options"]["scales"]["xAxes"][0]["display"], false);
+    assert_eq!(chart["options"]["scales"]["yAxes"][0]["display"], false);
+    assert_eq!(body["width"], 300);
+    assert_eq!(body["height"], 40);
+
+    // The original client is unchanged
+    let original = client.build_json_body();
+    assert_eq!(original["chart"]["data"]["datasets"][0]["data"], serde_json::json!([1, 2]));
+
+    let v3 = client.clone().version("4".to_string()).legend_client().unwrap().build_json_body();
+    assert_eq!(v3["chart"]["options"]["plugins"]["legend"]["display"], true);
+    assert_eq!(v3["chart"]["options"]["scales"]["x"]["display"], false);
+
+    let js = QuickchartClient::new().chart("{type: 'bar'}".to_string());
+    assert!(matches!(js.legend_client(), Err(QCError::InvalidConfig(_))));
+}
+
+#[tokio::test]
+async fn test_legend_image_posts_stripped_config() {
+    let server = MockServer::start(vec![MockResponse::new(200, b"legend".to_vec())]);
+    let client = mock_client(&server)
+        .chart(r#"{"type":"bar","data":{"datasets":[{"label":"A","data":[1]}]}}"#.to_string());
+
+    assert_eq!(client.legend_image().await.unwrap(), b"legend");
+    let body = server.requests()[0].json();
+    assert_eq!(body["chart"]["data"]["datasets"][0]["data"], serde_json::json!([]));
+}
+
+#[tokio::test]
+async fn test_fallback_image_on_connection_failure() {
+    let mut client = QuickchartClient::new()
+        .chart(r#"{"type":"bar"}"#.to_string())
+        .fallback_image(b"placeholder".to_vec());
+    client.base_url = Url::parse(&unreachable_url()).unwrap();
+
+    assert_eq!(client.post().await.unwrap(), b"placeholder");
+
+    let mut buf = b"stale".to_vec();
+    client.post_into(&mut buf).await.unwrap();
+    assert_eq!(buf, b"placeholder");
+
+    let path = std::env::temp_dir().join(format!("quickchart-fallback-{}.png", std::process::id()));
+    client.to_file(&path).await.unwrap();
+    assert_eq!(std::fs::read(&path).unwrap(), b"placeholder");
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn test_fallback_image_on_server_error() {
+    let server = MockServer::start(vec![MockResponse::new(503, "unavailable")]);
+    let client = mock_client(&server).fallback_image(b"placeholder".to_vec());
+
+    assert_eq!(client.post().await.unwrap(), b"placeholder");
+}
+
+#[tokio::test]
+async fn test_fallback_image_not_used_for_client_errors() {
+    let server = MockServer::start(vec![MockResponse::new(400, "bad chart")]);
+    let client = mock_client(&server).fallback_image(b"placeholder".to_vec());
+
+    let error = client.post().await.unwrap_err();
+    assert!(matches!(error, QCError::ApiError { status: 400, .. }));
+}
+
+#[test]
+fn test_color_scheme() {
+    let client = QuickchartClient::new()
+        .chart(r#"{"type":"bar","options":{"plugins":{"colorschemes":{"override":true}}}}"#.to_string())
+        .color_scheme("brewer.Paired12".to_string());
+
+    assert_eq!(
+        client.build_json_body()["chart"]["options"]["plugins"]["colorschemes"],
+        serde_json::json!({ "scheme": "brewer.Paired12", "override": true })
+    );
+}
+
+#[test]
+fn test_auto_size() {
+    let base = QuickchartClient::new()
+        .chart(r#"{"type":"wordCloud"}"#.to_string())
+        .width(800)
+        .height(400);
+    let client = base.clone().auto_size();
+
+    let url = Url::parse(&client.get_url().unwrap()).unwrap();
+    assert!(url.query_pairs().all(|(key, _)| key != "w" && key != "h"));
+    let body = client.build_json_body();
+    assert!(body.get("width").is_none() && body.get("height").is_none());
+
+    assert!(base.get_url().unwrap().contains("w=800"));
+}
+
+#[test]
+fn test_number_format() {
+    let client = QuickchartClient::new()
+        .chart(r#"{"type":"bar","data":{"datasets":[{"data":[12500]}]}}"#.to_string())
+        .number_format(crate::NumberFormat::currency("USD".to_string()))
+        .data_labels(crate::DataLabels::new());
+
+    let callback = "function(value) { return new Intl.NumberFormat(undefined, \
+                    {\"style\":\"currency\",\"currency\":\"USD\"}).format(value); }";
+    let config = client.resolved_chart();
+    assert!(config.contains(&format!("\"callback\":{}", callback)));
+    assert!(config.contains(&format!("\"formatter\":{}", callback)));
+    assert!(serde_json::from_str::<serde_json::Value>(&config).is_err());
+
+    // An explicit data label formatter wins
+    let labels = crate::DataLabels::new().formatter(RawJs::new("(v) => v".to_string()));
+    let client = client.data_labels(labels);
+    let config = client.resolved_chart();
+    assert!(config.contains("\"formatter\":(v) => v"));
+    assert!(config.contains(&format!("\"callback\":{}", callback)));
+}
+
+#[test]
+fn test_apply() {
+    let with_labels = |labels: bool| {
+        QuickchartClient::new()
+            .chart(r#"{"type":"bar"}"#.to_string())
+            .apply(|c| if labels { c.data_labels(crate::DataLabels::new()).width(600) } else { c })
+            .height(200)
+    };
+
+    let body = with_labels(true).build_json_body();
+    assert_eq!(body["chart"]["options"]["plugins"]["datalabels"]["display"], true);
+    assert_eq!(body["width"], 600);
+    assert_eq!(body["height"], 200);
+
+    let body = with_labels(false).build_json_body();
+    assert!(body["chart"]["options"].get("plugins").is_none());
+    assert!(body.get("width").is_none());
+    assert_eq!(body["height"], 200);
+}
+
+#[tokio::test]
+async fn test_short_url_with_qr() {
+    let short_url = "https://quickchart.io/chart/render/zf-abc?x=1";
+    let server = MockServer::start(vec![
+        MockResponse::new(200, format!(r#"{{"success":true,"url":"{}"}}"#, short_url)),
+        MockResponse::new(200, "qr-png"),
+    ]);
+    let client = mock_client(&server);
+
+    let (url, qr_code) = client.short_url_with_qr().await.unwrap();
+    assert_eq!(url, short_url);
+    assert_eq!(qr_code, b"qr-png");
+
+    let requests = server.requests();
+    assert_eq!(requests[0].path, "/chart/create");
+    assert_eq!(requests[1].method, "GET");
+    let qr_url = Url::parse(&format!("http://localhost{}", requests[1].path)).unwrap();
+    assert_eq!(qr_url.path(), "/qr");
+    let query: Vec<(String, String)> = qr_url.query_pairs().into_owned().collect();
+    assert_eq!(
+        query,
+        vec![("text".to_string(), short_url.to_string()), ("format".to_string(), "png".to_string())]
+    );
+}
+
+#[tokio::test]
+async fn test_short_url_with_qr_stops_when_short_url_fails() {
+    let server = MockServer::start(vec![MockResponse::new(400, "bad chart")]);
+    let client = mock_client(&server);
+
+    assert!(client.short_url_with_qr().await.is_err());
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[test]
+fn test_plot_background() {
+    let client = QuickchartClient::new()
+        .chart(r#"{"type":"line","plugins":[{"id":"existing"}]}"#.to_string())
+        .background_color("white".to_string())
+        .plot_background("rgba(255, 0, 0, 0.1)".to_string());
+
+    let config = client.resolved_chart();
+    assert!(config.contains(
+        r#""plugins":[{"id":"existing"},{"id":"plotBackground","beforeDraw":function(chart) {"#
+    ));
+    assert!(config.contains(r#"ctx.fillStyle = "rgba(255, 0, 0, 0.1)";"#));
+    assert!(config.contains(r#""id":"plotBackground""#));
+    assert!(client.get_url().unwrap().contains("bkg=white"));
+
+    let client = QuickchartClient::new()
+        .chart(r#"{"type":"line"}"#.to_string())
+        .plot_background("#eee".to_string());
+    assert!(client
+        .resolved_chart()
+        .contains(r#""plugins":[{"id":"plotBackground","beforeDraw":function(chart) {"#));
+}
+
+#[test]
+fn test_span_gaps() {
+    let client = QuickchartClient::new()
+        .chart(r#"{"type":"line","data":{"datasets":[{"data":[1,null,3]}]},"options":{"legend":{}}}"#.to_string())
+        .span_gaps(true);
+
+    let options = &client.build_json_body()["chart"]["options"];
+    assert_eq!(options["spanGaps"], true);
+    assert_eq!(options["legend"], serde_json::json!({}));
+
+    let options = &client.span_gaps(false).build_json_body()["chart"]["options"];
+    assert_eq!(options["spanGaps"], false);
+}
+
+#[tokio::test]
+async fn test_http1_only() {
+    let server = MockServer::start(vec![MockResponse::new(200, "image")]);
+    let client = mock_client(&server).http1_only();
+
+    assert!(client.http_settings.http1_only);
+    assert_eq!(client.post().await.unwrap(), b"image");
+}
+
+#[test]
+fn test_legend_label_styling() {
+    let chart = r#"{"type":"line","options":{"legend":{"position":"bottom","labels":{"fontSize":14}},
+        "plugins":{"legend":{"labels":{"color":"red"}}}}}"#;
+    let client = QuickchartClient::new()
+        .chart(chart.to_string())
+        .legend_point_style(true)
+        .legend_box_width(12);
+
+    let v2 = client.build_json_body();
+    assert_eq!(
+        v2["chart"]["options"]["legend"],
+        serde_json::json!({
+            "position": "bottom",
+            "labels": { "fontSize": 14, "usePointStyle": true, "boxWidth": 12 },
+        })
+    );
+
+    for version in ["3", "4.4.0"] {
+        let body = client.clone().version(version.to_string()).build_json_body();
+        assert_eq!(
+            body["chart"]["options"]["plugins"]["legend"]["labels"],
+            serde_json::json!({ "color": "red", "usePointStyle": true, "boxWidth": 12 })
+        );
+        assert!(body["chart"]["options"]["legend"]["labels"].get("boxWidth").is_none());
+    }
+}
+
+#[test]
+fn test_check_url() {
+    let small = QuickchartClient::new()
+        .chart(r#"{"type":"bar","data":{"datasets":[{"data":[1,2]}]}}"#.to_string());
+    let report = small.check_url().unwrap();
+    assert_eq!(report.url, small.get_url().unwrap());
+    assert_eq!(report.byte_length, report.url.len());
+    assert!(report.within_get_limit);
+
+    let data: Vec<usize> = (0..5000).collect();
+    let chart = serde_json::json!({ "type": "line", "data": { "datasets": [{ "data": data }] } });
+    let large = QuickchartClient::new().chart(chart.to_string());
+    let report = large.check_url().unwrap();
+    assert!(report.byte_length > MAX_GET_URL_LENGTH);
+    assert!(!report.within_get_limit);
+}
+
+#[test]
+fn test_is_url_too_long() {
+    let client = QuickchartClient::new().chart(r#"{"type":"bar"}"#.to_string());
+    let length = client.get_url().unwrap().len();
+    assert!(!client.is_url_too_long(length).unwrap());
+    assert!(client.is_url_too_long(length - 1).unwrap());
+    assert!(!client.is_url_too_long(DEFAULT_URL_LENGTH_LIMIT).unwrap());
+
+    let empty = QuickchartClient::new();
+    assert!(matches!(empty.is_url_too_long(DEFAULT_URL_LENGTH_LIMIT), Err(QCError::EmptyChart)));
+}
+
+#[tokio::test]
+async fn test_json_only() {
+    let server = MockServer::start(vec![MockResponse::new(200, "image")]);
+    let json = mock_client(&server).json_only(true);
+    assert!(json.get_url().is_ok());
+    assert_eq!(json.post().await.unwrap(), b"image");
+
+    let js = json.clone().chart("{type: 'bar', options: {onClick: function() {}}}".to_string());
+    assert!(matches!(js.get_url(), Err(QCError::InvalidConfig(_))));
+    assert!(matches!(js.url_parts(), Err(QCError::InvalidConfig(_))));
+    assert!(matches!(js.post().await, Err(QCError::InvalidConfig(_))));
+    assert!(matches!(js.get_short_url().await, Err(QCError::InvalidConfig(_))));
+    assert_eq!(server.requests().len(), 1);
+
+    // Helpers that add JavaScript are rejected too
+    let formatted = json.clone().number_format(crate::NumberFormat::decimal());
+    assert!(matches!(formatted.get_url(), Err(QCError::InvalidConfig(_))));
+
+    assert!(js.json_only(false).get_url().is_ok());
+}
+
+#[test]
+fn test_footer() {
+    let client = QuickchartClient::new()
+        .chart(r#"{"type":"bar","options":{"plugins":{"legend":{"display":false}}}}"#.to_string())
+        .footer("Source: \"ACME\" survey".to_string());
+
+    let body = client.clone().version("4".to_string()).build_json_body();
+    assert_eq!(
+        body["chart"]["options"]["plugins"],
+        serde_json::json!({
+            "legend": { "display": false },
+            "subtitle": { "display": true, "text": "Source: \"ACME\" survey", "position": "bottom" },
+        })
+    );
+
+    let config = client.resolved_chart();
+    assert!(config.contains(r#"ctx.fillText("Source: \"ACME\" survey", chart.width / 2"#));
+    assert!(config.contains(r#""id":"footer""#));
+    assert!(config.contains(r#""padding":{"bottom":20.0}"#));
+}
+
+#[tokio::test]
+async fn test_post_with_content_type() {
+    let server = MockServer::start(vec![
+        MockResponse::new(200, "<svg/>").header("Content-Type", "image/svg+xml"),
+        MockResponse::new(200, "image"),
+    ]);
+    let client = mock_client(&server);
+
+    let (bytes, content_type) = client.post_with_content_type().await.unwrap();
+    assert_eq!(bytes, b"<svg/>");
+    assert_eq!(content_type.as_deref(), Some("image/svg+xml"));
+
+    let (bytes, content_type) = client.post_with_content_type().await.unwrap();
+    assert_eq!(bytes, b"image");
+    assert_eq!(content_type, None);
+}
+
+#[tokio::test]
+async fn test_post_with_config() {
+    let server = MockServer::start(vec![MockResponse::new(200, "image")]);
+    let client = mock_client(&server).chart(
+        r#"{ "type": "bar", "data": { "datasets": [ { "data": [1, 2] } ] } }"#.to_string(),
+    );
+
+    let (image, config) = client.post_with_config().await.unwrap();
+    assert_eq!(image, b"image");
+    assert!(!config.contains(' '));
+    let sent = server.requests()[0].json();
+    assert_eq!(serde_json::from_str::<serde_json::Value>(&config).unwrap(), sent["chart"]);
+    assert_eq!(sent["chart"]["options"]["animation"], false);
+
+    let server = MockServer::start(vec![MockResponse::new(200, "image")]);
+    let js_config = "{type: 'bar', options: {onClick: function() {}}}";
+    let client = mock_client(&server).chart(js_config.to_string());
+    let (_, config) = client.post_with_config().await.unwrap();
+    assert_eq!(config, js_config);
+    assert_eq!(server.requests()[0].json()["chart"], js_config);
+}
+
+#[test]
+fn test_format_typed() {
+    for (format, token) in [
+        (ImageFormat::Png, "png"),
+        (ImageFormat::Svg, "svg"),
+        (ImageFormat::WebP, "webp"),
+        (ImageFormat::Pdf, "pdf"),
+    ] {
+        assert_eq!(format.to_string(), token);
+
+        let client = QuickchartClient::new()
+            .chart(r#"{"type":"bar"}"#.to_string())
+            .format_typed(format);
+        assert!(client.get_url().unwrap().ends_with(&format!("&f={}", token)));
+        assert_eq!(client.build_json_body()["format"], token);
+    }
+}
+
+#[test]
+fn test_base_url() {
+    let chart = r#"{"type":"bar"}"#.to_string();
+    for (base, expected) in [
+        ("http://localhost:3400", "http://localhost:3400/chart?"),
+        ("http://localhost:3400/", "http://localhost:3400/chart?"),
+        ("https://example.com/quickchart", "https://example.com/quickchart/chart?"),
+        ("https://example.com/quickchart/", "https://example.com/quickchart/chart?"),
+    ] {
+        let client = QuickchartClient::new().base_url(base).unwrap().chart(chart.clone());
+        assert!(client.get_url().unwrap().starts_with(expected), "{}", base);
+    }
+
+    let invalid = QuickchartClient::new().base_url("not a url");
+    assert!(matches!(invalid, Err(QCError::UrlParseError(_))));
+}
+
+#[tokio::test]
+async fn test_base_url_with_path_prefix() {
+    let server = MockServer::start(vec![
+        MockResponse::new(200, "image"),
+        MockResponse::new(200, r#"{"success":true,"url":"https://example.com/s/1"}"#),
+    ]);
+    let client = QuickchartClient::new()
+        .base_url(format!("{}/quickchart", server.url().trim_end_matches('/')))
+        .unwrap()
+        .chart(r#"{"type":"bar"}"#.to_string());
+
+    client.post().await.unwrap();
+    client.get_short_url().await.unwrap();
+    let requests = server.requests();
+    assert_eq!(requests[0].path, "/quickchart/chart");
+    assert_eq!(requests[1].path, "/quickchart/chart/create");
+}
+
+#[tokio::test]
+async fn test_timeout() {
+    let server = MockServer::start(vec![
+        MockResponse::new(200, "image").delay(std::time::Duration::from_millis(500)),
+    ]);
+    let client = mock_client(&server).timeout(std::time::Duration::from_millis(1));
+
+    let error = client.post().await.unwrap_err();
+    assert!(matches!(error, QCError::HttpError(ref e) if e.is_timeout()));
+}
+
+#[tokio::test]
+async fn test_api_error_from_json_body() {
+    let server = MockServer::start(vec![MockResponse::new(
+        400,
+        r#"{"success":false,"error":"Unexpected token } in JSON"}"#,
+    )]);
+
+    let error = mock_client(&server).post().await.unwrap_err();
+    assert!(matches!(
+        error,
+        QCError::ApiError { status: 400, ref message } if message == "Unexpected token } in JSON"
+    ));
+    assert_eq!(error.to_string(), "QuickChart API error (400): Unexpected token } in JSON");
+}
+
+#[tokio::test]
+async fn test_api_error_falls_back_to_body_text() {
+    let server = MockServer::start(vec![MockResponse::new(500, "Internal Server Error\n")]);
+
+    let error = mock_client(&server).get_short_url().await.unwrap_err();
+    assert!(matches!(
+        error,
+        QCError::ApiError { status: 500, ref message } if message == "Internal Server Error"
+    ));
+}
+
+#[test]
+fn test_getters() {
+    let client = QuickchartClient::new();
+    assert_eq!(client.get_chart(), "");
+    assert_eq!(client.get_width(), None);
+    assert_eq!(client.get_format(), None);
+    assert_eq!(client.get_base_url(), "https://quickchart.io/");
+    assert!(client.get_fallback_base_urls().is_empty());
+
+    let client = client
+        .chart(r#"{"type":"bar"}"#.to_string())
+        .width(800)
+        .height(400)
+        .device_pixel_ratio(1.5)
+        .background_color("white".to_string())
+        .version("4".to_string())
+        .format("svg".to_string())
+        .default_format("png".to_string())
+        .url_escape(UrlEscape::NonAlphanumeric)
+        .json_only(true)
+        .base_url("http://localhost:3400")
+        .unwrap()
+        .fallback_base_urls(vec!["http://backup:3400".to_string()])
+        .unwrap()
+        .fallback_image(b"placeholder".to_vec());
+
+    assert_eq!(client.get_chart(), r#"{"type":"bar"}"#);
+    assert_eq!(client.get_width(), Some(800));
+    assert_eq!(client.get_height(), Some(400));
+    assert_eq!(client.get_device_pixel_ratio(), Some(1.5));
+    assert_eq!(client.get_background_color(), Some("white"));
+    assert_eq!(client.get_version(), Some("4"));
+    assert_eq!(client.get_format(), Some("svg"));
+    assert_eq!(client.get_default_format(), Some("png"));
+    assert_eq!(client.get_url_escape(), UrlEscape::NonAlphanumeric);
+    assert!(client.get_json_only());
+    assert_eq!(client.get_base_url(), "http://localhost:3400/");
+    assert_eq!(client.get_fallback_base_urls(), vec!["http://backup:3400/"]);
+    assert_eq!(client.get_fallback_image(), Some(&b"placeholder"[..]));
+}
+
+#[test]
+fn test_clone_and_debug() {
+    let base = QuickchartClient::new()
+        .chart(r#"{"type":"bar"}"#.to_string())
+        .width(640)
+        .background_color("white".to_string());
+
+    let line = base.clone().chart(r#"{"type":"line"}"#.to_string());
+    assert_eq!(base.get_chart(), r#"{"type":"bar"}"#);
+    assert_eq!(line.get_chart(), r#"{"type":"line"}"#);
+    assert_eq!(line.get_width(), Some(640));
+    assert_eq!(line.get_background_color(), Some("white"));
+
+    let debug = format!("{:?}", line);
+    assert!(debug.starts_with("QuickchartClient { client: reqwest::Client { .. }"));
+    assert!(debug.contains(r#"chart: "{\"type\":\"line\"}""#));
+    assert!(debug.contains("width: Some(640)"));
+    assert!(debug.contains(r#"base_url: "https://quickchart.io/""#));
+}
+
+#[tokio::test]
+async fn test_empty_chart() {
+    let server = MockServer::start(vec![MockResponse::new(200, "image")]);
+    let client = mock_client(&server).chart("  \n".to_string());
+
+    assert!(matches!(client.get_url(), Err(QCError::EmptyChart)));
+    assert!(matches!(client.post().await, Err(QCError::EmptyChart)));
+    assert!(matches!(client.get_short_url().await, Err(QCError::EmptyChart)));
+    let path = std::env::temp_dir().join(format!("quickchart-empty-{}.png", std::process::id()));
+    assert!(matches!(client.to_file(&path).await, Err(QCError::EmptyChart)));
+    assert!(!path.exists());
+    assert!(server.requests().is_empty());
+
+    // A config built up through config_mut() counts as set
+    let mut client = QuickchartClient::new();
+    client.config_mut().unwrap()["type"] = "bar".into();
+    assert!(client.get_url().is_ok());
+}
+
+#[test]
+fn test_qr_url() {
+    let client = QuickchartClient::new()
+        .base_url("http://localhost:3400")
+        .unwrap()
+        .chart(r#"{"type":"bar"}"#.to_string())
+        .width(800)
+        .format("webp".to_string());
+
+    let qr = crate::QrCode::new("hello world".to_string())
+        .size(200)
+        .margin(2)
+        .dark_color("1f4e79".to_string())
+        .light_color("ffffff".to_string())
+        .format("svg".to_string());
+    assert_eq!(
+        client.qr_url(&qr).unwrap(),
+        "http://localhost:3400/qr?text=hello+world&size=200&margin=2&dark=1f4e79&light=ffffff&format=svg"
+    );
+
+    let plain = crate::QrCode::new("a&b".to_string());
+    assert_eq!(client.qr_url(&plain).unwrap(), "http://localhost:3400/qr?text=a%26b");
+}
+
+#[tokio::test]
+async fn test_qr_post() {
+    let server = MockServer::start(vec![MockResponse::new(200, "qr-image")]);
+    let client = mock_client(&server).width(800);
+
+    let qr = crate::QrCode::new("hello".to_string()).size(100);
+    assert_eq!(client.qr_post(&qr).await.unwrap(), b"qr-image");
+
+    let request = &server.requests()[0];
+    assert_eq!(request.method, "GET");
+    assert_eq!(request.path, "/qr?text=hello&size=100");
+}
+
+#[tokio::test]
+async fn test_to_file_infers_format_from_extension() {
+    let server = MockServer::start(vec![MockResponse::new(200, "<svg/>")]);
+    let path = std::env::temp_dir().join(format!("quickchart-infer-{}.SVG", std::process::id()));
+
+    let client = mock_client(&server).default_format("png".to_string());
+    client.to_file(&path).await.unwrap();
+    assert_eq!(std::fs::read(&path).unwrap(), b"<svg/>");
+    assert_eq!(server.requests()[0].json()["format"], "svg");
+    // The client itself is left unchanged
+    assert_eq!(client.get_format(), None);
+
+    // A matching explicit format is fine, a conflicting one is rejected before any request
+    mock_client(&server).format("svg".to_string()).to_file(&path).await.unwrap();
+    let mismatch = mock_client(&server).format("png".to_string()).to_file(&path).await;
+    assert!(matches!(
+        mismatch,
+        Err(QCError::FormatMismatch { ref extension, ref format })
+            if extension == "SVG" && format == "png"
+    ));
+    assert_eq!(server.requests().len(), 2);
+    std::fs::remove_file(&path).unwrap();
+
+    // Other extensions keep the configured format
+    let path = std::env::temp_dir().join(format!("quickchart-infer-{}.img", std::process::id()));
+    mock_client(&server).format("webp".to_string()).to_file(&path).await.unwrap();
+    assert_eq!(server.requests()[2].json()["format"], "webp");
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn test_retries_transient_failures() {
+    let server = MockServer::start(vec![
+        MockResponse::new(503, "busy"),
+        MockResponse::new(429, "slow down").header("Retry-After", "0"),
+        MockResponse::new(200, "image"),
+    ]);
+    let client = mock_client(&server)
+        .retries(2)
+        .retry_base_delay(Duration::from_millis(1));
+
+    assert_eq!(client.post().await.unwrap(), b"image");
+    assert_eq!(server.requests().len(), 3);
+
+    // Running out of retries returns the last error
+    let server = MockServer::start(vec![MockResponse::new(503, "busy")]);
+    let client = mock_client(&server).retries(1).retry_base_delay(Duration::from_millis(1));
+    assert!(matches!(client.post().await, Err(QCError::ApiError { status: 503, .. })));
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn test_retries_skip_client_errors() {
+    let server = MockServer::start(vec![MockResponse::new(400, "bad chart")]);
+    let client = mock_client(&server).retries(3);
+
+    assert!(matches!(client.post().await, Err(QCError::ApiError { status: 400, .. })));
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[test]
+fn test_retry_delay() {
+    let client = QuickchartClient::new().retry_base_delay(Duration::from_millis(100));
+    assert_eq!(client.retry_delay(0, None), Duration::from_millis(100));
+    assert_eq!(client.retry_delay(3, None), Duration::from_millis(800));
+    assert_eq!(client.retry_delay(3, Some(Duration::from_secs(2))), Duration::from_secs(2));
+    // A huge Retry-After is capped
+    assert_eq!(client.retry_delay(0, Some(Duration::from_secs(86_400))), MAX_RETRY_AFTER);
+
+    let mut headers = reqwest::header::HeaderMap::new();
+    headers.insert(reqwest::header::RETRY_AFTER, "7".parse().unwrap());
+    assert_eq!(QuickchartClient::retry_after(&headers), Some(Duration::from_secs(7)));
+    headers.insert(reqwest::header::RETRY_AFTER, "99999999999".parse().unwrap());
+    let retry_after = QuickchartClient::retry_after(&headers);
+    assert_eq!(client.retry_delay(0, retry_after), MAX_RETRY_AFTER);
+    headers.insert(reqwest::header::RETRY_AFTER, "Wed, 21 Oct 2026 07:28:00 GMT".parse().unwrap());
+    assert_eq!(QuickchartClient::retry_after(&headers), None);
+}
+
+#[tokio::test]
+async fn test_device_pixel_ratio_validation() {
+    let server = MockServer::start(vec![MockResponse::new(200, "image")]);
+
+    let valid = mock_client(&server).device_pixel_ratio(2.0);
+    assert!(valid.get_url().unwrap().contains("devicePixelRatio=2"));
+    valid.post().await.unwrap();
+    assert_eq!(server.requests()[0].json()["devicePixelRatio"], 2.0);
+
+    for dpr in [f32::NAN, 0.0, -1.0, f32::INFINITY] {
+        let client = mock_client(&server).device_pixel_ratio(dpr);
+        // Building the body must not panic even though the ratio is rejected
+        client.build_json_body();
+        assert!(matches!(client.get_url(), Err(QCError::InvalidDevicePixelRatio(_))));
+        assert!(matches!(client.post().await, Err(QCError::InvalidDevicePixelRatio(_))));
+    }
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[test]
+fn test_size_and_aspect_ratio() {
+    let client = QuickchartClient::new().size(640, 480);
+    assert_eq!((client.get_width(), client.get_height()), (Some(640), Some(480)));
+
+    let cases = [(800, 16.0 / 9.0, 450), (500, 4.0 / 3.0, 375), (1000, 3.0, 333), (100, 1.5, 67)];
+    for (width, ratio, height) in cases {
+        let client = QuickchartClient::new()
+            .chart(r#"{"type":"bar"}"#.to_string())
+            .aspect_ratio(width, ratio);
+        assert_eq!(client.get_width(), Some(width));
+        assert_eq!(client.get_height(), Some(height), "{} at {}", width, ratio);
+        assert_eq!(client.build_json_body()["height"], height);
+    }
+}
+
+#[tokio::test]
+async fn test_aspect_ratio_rejects_zero() {
+    let server = MockServer::start(vec![MockResponse::new(200, "image")]);
+    for ratio in [0.0, -1.5, f32::NAN, f32::INFINITY] {
+        let client = mock_client(&server).aspect_ratio(800, ratio);
+        assert_eq!(client.get_width(), Some(800));
+        assert_eq!(client.get_height(), None);
+        assert!(matches!(client.get_url(), Err(QCError::InvalidAspectRatio(_))));
+        assert!(matches!(client.post().await, Err(QCError::InvalidAspectRatio(_))));
+    }
+    assert!(server.requests().is_empty());
+
+    // A later valid ratio or any other dimension setter replaces the invalid one
+    let client = mock_client(&server).aspect_ratio(800, 0.0).aspect_ratio(800, 2.0);
+    assert_eq!(client.get_height(), Some(400));
+    assert!(client.get_url().is_ok());
+    let invalid = || mock_client(&server).aspect_ratio(800, 0.0);
+    assert!(invalid().size(800, 450).get_url().is_ok());
+    assert!(invalid().width(640).get_url().is_ok());
+    assert!(invalid().height(450).get_url().is_ok());
+    assert!(invalid().auto_size().get_url().is_ok());
+}
+
+#[test]
+fn test_compact_chart_preserves_key_order() {
+    assert_eq!(QuickchartClient::compact_chart(r#"{"b": 1, "a": 2}"#), r#"{"b":1,"a":2}"#);
+
+    let client = QuickchartClient::new()
+        .chart(r#"{"type": "bar", "data": {"labels": ["A"], "datasets": [{"data": [1]}]}}"#.to_string())
+        .width(400);
+    let url = Url::parse(&client.get_url().unwrap()).unwrap();
+    let (_, c) = url.query_pairs().find(|(key, _)| key == "c").unwrap();
+    assert!(c.starts_with(r#"{"type":"bar","data":{"labels":["A"],"datasets":[{"data":[1]}]}"#));
+}
+
+#[tokio::test]
+async fn test_render_template() {
+    let server = MockServer::start(vec![MockResponse::new(200, "template image")]);
+    let client = mock_client(&server).width(400).height(200).format("svg".to_string());
+    let template = crate::ChartTemplate::new("zf abc".to_string())
+        .param("title".to_string(), "Q1 & Q2".to_string())
+        .param("data1".to_string(), "1,2".to_string());
+
+    let path = "/chart/render/zf%20abc?title=Q1+%26+Q2&data1=1%2C2&w=400&h=200&f=svg";
+    assert_eq!(client.template_url(&template).unwrap(), format!("{}{}", server.url(), path));
+
+    assert_eq!(client.render_template(&template).await.unwrap(), b"template image");
+    let request = &server.requests()[0];
+    assert_eq!(request.method, "GET");
+    assert_eq!(request.path, path);
+}
+
+#[test]
+fn test_sparkline() {
+    let client = QuickchartClient::new().sparkline(vec![1.0, 4.0, 2.0]);
+    let body = client.build_json_body();
+    assert_eq!(body["width"], 100);
+    assert_eq!(body["height"], 30);
+
+    let chart = &body["chart"];
+    assert_eq!(chart["type"], "line");
+    assert_eq!(chart["data"]["labels"], serde_json::json!([0, 1, 2]));
+    assert_eq!(chart["data"]["datasets"][0]["data"], serde_json::json!([1.0, 4.0, 2.0]));
+    assert_eq!(chart["options"]["legend"]["display"], false);
+    assert_eq!(chart["options"]["tooltips"]["enabled"], false);
+    assert_eq!(chart["options"]["scales"]["xAxes"][0]["display"], false);
+    assert_eq!(chart["options"]["scales"]["yAxes"][0]["display"], false);
+    assert_eq!(chart["options"]["elements"]["point"]["radius"], 0);
+
+    // Explicit dimensions win, before or after, and Chart.js 3+ layouts are used when pinned
+    let client = QuickchartClient::new()
+        .width(240)
+        .sparkline(vec![1.0])
+        .height(60)
+        .version("4".to_string());
+    let body = client.build_json_body();
+    assert_eq!((body["width"].clone(), body["height"].clone()), (240.into(), 60.into()));
+    assert_eq!(body["chart"]["options"]["plugins"]["legend"]["display"], false);
+    assert_eq!(body["chart"]["options"]["scales"]["x"]["display"], false);
+}
+
+#[test]
+fn test_sparkline_replaced_by_another_chart() {
+    let sparkline = QuickchartClient::new().sparkline(vec![1.0, 2.0]);
+    for client in [
+        sparkline.clone().chart(r#"{"type":"bar"}"#.to_string()),
+        sparkline.clone().chart_value(serde_json::json!({ "type": "bar" })),
+    ] {
+        let body = client.build_json_body();
+        let expected = serde_json::json!({ "type": "bar", "options": { "animation": false } });
+        assert_eq!(body["chart"], expected);
+        assert!(body.get("width").is_none() && body.get("height").is_none());
+        let url = client.get_url().unwrap();
+        assert!(!url.contains("w=100") && !url.contains("h=30"));
+    }
+}
+
+#[test]
+fn test_extra_param() {
+    let client = QuickchartClient::new()
+        .chart(r#"{"type":"bar"}"#.to_string())
+        .width(400)
+        .extra_param("scale".to_string(), "2".to_string())
+        .extra_param("w".to_string(), "640".to_string())
+        .extra_param("c".to_string(), "{}".to_string())
+        .extra_param("chart".to_string(), "{}".to_string());
+
+    let url = Url::parse(&client.get_url().unwrap()).unwrap();
+    let pairs: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+    let keys: Vec<&str> = pairs.iter().map(|(key, _)| key.as_str()).collect();
+    assert_eq!(keys, ["c", "scale", "w", "chart"]);
+    assert_eq!(pairs[0].1, client.url_parts().unwrap().1[0].1);
+    assert_eq!(pairs[2].1, "640");
+
+    let body = client.build_json_body();
+    assert_eq!(body["chart"]["type"], "bar");
+    assert_eq!(body["scale"], "2");
+    assert_eq!(body["w"], "640");
+    assert_eq!(body["width"], 400);
+    assert_eq!(body["c"], "{}");
+}
+
+#[tokio::test]
+async fn test_write_to() {
+    let image: Vec<u8> = (0..=255).cycle().take(200_000).collect();
+    let server = MockServer::start(vec![
+        MockResponse::new(200, image.clone()),
+        MockResponse::new(503, "down"),
+    ]);
+    let client = mock_client(&server);
+
+    let mut sink = Vec::new();
+    let written = client.write_to(&mut sink).await.unwrap();
+    // The mock server sends the body length as Content-Length
+    assert_eq!(written, image.len() as u64);
+    assert_eq!(sink, image);
+
+    let mut sink = Vec::new();
+    assert!(matches!(client.write_to(&mut sink).await, Err(QCError::ApiError { status: 503, .. })));
+    assert!(sink.is_empty());
+
+    let mut sink = Vec::new();
+    let client = client.fallback_image(b"placeholder".to_vec());
+    assert_eq!(client.write_to(&mut sink).await.unwrap(), 11);
+    assert_eq!(sink, b"placeholder");
+}
+
+#[test]
+fn test_chart_from_file() {
+    let config = "{\n  \"type\": \"pie\",\n  \"data\": {\"datasets\": [{\"data\": [1, 2]}]}\n}\n";
+    let path = std::env::temp_dir().join(format!("quickchart-config-{}.json", std::process::id()));
+    std::fs::write(&path, config).unwrap();
+
+    let client = QuickchartClient::new().width(300).chart_from_file(&path).unwrap();
+    assert_eq!(client.get_chart(), config);
+    assert_eq!(client.get_width(), Some(300));
+    assert_eq!(client.build_json_body()["chart"]["type"], "pie");
+    std::fs::remove_file(&path).unwrap();
+
+    let missing = QuickchartClient::new().chart_from_file(&path);
+    assert!(matches!(missing, Err(QCError::IoError(_))));
+}
+
+#[test]
+fn test_get_url_parsed() {
+    let client = QuickchartClient::new()
+        .chart("{type: 'bar', data: {labels: ['A B']}}".to_string())
+        .url_escape(UrlEscape::NonAlphanumeric)
+        .height(250);
+
+    let url = client.get_url_parsed().unwrap();
+    assert_eq!(url.as_str(), client.get_url().unwrap());
+    assert_eq!(url.path(), "/chart");
+    let pairs: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+    assert_eq!(pairs, client.url_parts().unwrap().1);
+
+    assert!(matches!(QuickchartClient::new().get_url_parsed(), Err(QCError::EmptyChart)));
+}
+
+#[test]
+fn test_dataset_builds_chart() {
+    let client = QuickchartClient::new()
+        .chart_type("line".to_string())
+        .labels(vec!["Q1".to_string(), "Q2".to_string()])
+        .dataset("Revenue".to_string(), vec![120.0, f64::NAN])
+        .dataset("Costs".to_string(), vec![80.0, 95.0]);
+
+    assert!(client.check_config().is_ok());
+    let chart = &client.build_json_body()["chart"];
+    assert_eq!(chart["type"], "line");
+    assert_eq!(chart["data"]["labels"], serde_json::json!(["Q1", "Q2"]));
+    assert_eq!(
+        chart["data"]["datasets"],
+        serde_json::json!([
+            {"label": "Revenue", "data": [120.0, null]},
+            {"label": "Costs", "data": [80.0, 95.0]},
+        ])
+    );
+}
+
+#[test]
+fn test_dataset_appends_to_config() {
+    let client = QuickchartClient::new()
+        .dataset("Added".to_string(), vec![1.0])
+        .chart(r#"{"type":"bar","data":{"datasets":[{"label":"Existing","data":[2]}]}}"#.to_string());
+
+    let datasets = &client.build_json_body()["chart"]["data"]["datasets"];
+    assert_eq!(datasets[0]["label"], "Existing");
+    assert_eq!(datasets[1]["label"], "Added");
+    assert_eq!(client.build_json_body()["chart"]["type"], "bar");
+}
+
+#[test]
+fn test_base_url_env_var() {
+    let local = Some("http://localhost:3400".to_string());
+    let client = QuickchartClient::new().with_base_url_var(local).unwrap();
+    assert_eq!(client.base_url.as_str(), "http://localhost:3400/");
+
+    let invalid = QuickchartClient::new().with_base_url_var(Some("not a url".to_string()));
+    assert!(matches!(invalid, Err(QCError::UrlParseError(_))));
+
+    for unset in [None, Some("  ".to_string())] {
+        let client = QuickchartClient::new().with_base_url_var(unset).unwrap();
+        assert_eq!(client.base_url.as_str(), "https://quickchart.io/");
+    }
+
+    let explicit = QuickchartClient::new()
+        .with_base_url_var(Some("http://localhost:3400".to_string()))
+        .unwrap()
+        .base_url("http://example.com")
+        .unwrap();
+    assert_eq!(explicit.base_url.as_str(), "http://example.com/");
+}
+
+#[test]
+fn test_graphviz_url() {
+    let client = QuickchartClient::new()
+        .base_url("http://localhost:3400")
+        .unwrap()
+        .width(800)
+        .format("webp".to_string());
+
+    let graph = crate::Graphviz::new("digraph { a -> b }".to_string())
+        .layout("neato".to_string())
+        .format("png".to_string())
+        .width(300)
+        .height(200);
+    assert_eq!(
+        client.graphviz_url(&graph).unwrap(),
+        "http://localhost:3400/graphviz?graph=digraph+%7B+a+-%3E+b+%7D&layout=neato&format=png&width=300&height=200"
+    );
+}
+
+#[tokio::test]
+async fn test_graphviz_post() {
+    let server = MockServer::start(vec![MockResponse::new(200, "<svg/>")]);
+    let client = mock_client(&server);
+
+    let graph = crate::Graphviz::new("graph{a--b}".to_string());
+    assert_eq!(client.graphviz_post(&graph).await.unwrap(), b"<svg/>");
+
+    let request = &server.requests()[0];
+    assert_eq!(request.method, "GET");
+    assert_eq!(request.path, "/graphviz?graph=graph%7Ba--b%7D");
+}
+
+#[tokio::test]
+async fn test_max_response_bytes() {
+    let server = MockServer::start(vec![
+        MockResponse::new(200, vec![0; 1000]),
+        MockResponse::new(200, vec![0; 1000]).without_content_length(),
+        MockResponse::new(200, vec![0; 1000]),
+        MockResponse::new(200, vec![0; 100]),
+    ]);
+    let client = mock_client(&server).max_response_bytes(100);
+    assert_eq!(client.get_max_response_bytes(), Some(100));
+
+    assert!(matches!(client.post().await, Err(QCError::ResponseTooLarge { limit: 100 })));
+    // Without a Content-Length the body is counted as it streams in
+    assert!(matches!(client.post().await, Err(QCError::ResponseTooLarge { limit: 100 })));
+
+    let mut sink = Vec::new();
+    assert!(matches!(
+        client.write_to(&mut sink).await,
+        Err(QCError::ResponseTooLarge { limit: 100 })
+    ));
+    assert!(sink.is_empty());
+
+    assert_eq!(client.post().await.unwrap().len(), 100);
+}
+
+#[tokio::test]
+async fn test_oversized_error_body_is_truncated() {
+    let huge = vec![b'x'; 8 * 1024 * 1024];
+    let server = MockServer::start(vec![
+        MockResponse::new(500, huge.clone()),
+        MockResponse::new(404, huge).without_content_length(),
+    ]);
+
+    let limited = mock_client(&server).max_response_bytes(100);
+    assert!(matches!(
+        limited.post().await,
+        Err(QCError::ApiError { status: 500, ref message }) if message.len() == 100
+    ));
+    // Without a limit, error bodies are still capped
+    assert!(matches!(
+        mock_client(&server).post().await,
+        Err(QCError::ApiError { status: 404, ref message }) if message.len() == MAX_ERROR_BODY_BYTES
+    ));
+}
+
+#[test]
+fn test_config_eq() {
+    let configure = |client: QuickchartClient| {
+        client
+            .chart(r#"{"type":"bar"}"#.to_string())
+            .width(800)
+            .version("4".to_string())
+            .span_gaps(true)
+    };
+    let build = || configure(QuickchartClient::new());
+    assert!(build().config_eq(&build()));
+    // The HTTP client isn't compared
+    assert!(build().config_eq(&configure(QuickchartClient::with_client(reqwest::Client::new()))));
+
+    assert!(!build().config_eq(&build().height(400)));
+    assert!(!build().config_eq(&build().span_gaps(false)));
+    assert!(!build().config_eq(&build().retries(2)));
+}
+
+#[tokio::test]
+async fn test_scale() {
+    let server = MockServer::start(vec![MockResponse::new(200, "image")]);
+    let client = mock_client(&server).width(400).scale(1.5);
+    assert_eq!(client.get_scale(), Some(1.5));
+
+    // Unset dimensions are scaled from QuickChart's defaults
+    assert!(client.get_url().unwrap().contains("w=600&h=450"));
+    client.post().await.unwrap();
+    let body = server.requests()[0].json();
+    assert_eq!((body["width"].clone(), body["height"].clone()), (600.into(), 450.into()));
+    assert!(body.get("devicePixelRatio").is_none());
+
+    for scale in [f32::NAN, 0.0, -1.0, f32::INFINITY] {
+        let client = mock_client(&server).scale(scale);
+        assert!(client.build_json_body().get("width").is_none());
+        assert!(matches!(client.get_url(), Err(QCError::InvalidScale(_))));
+        assert!(matches!(client.post().await, Err(QCError::InvalidScale(_))));
+    }
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[test]
+fn test_version_typed() {
+    for (version, token) in [
+        (crate::ChartVersion::V2, "2"),
+        (crate::ChartVersion::V3, "3"),
+        (crate::ChartVersion::V4, "4"),
+    ] {
+        let client = QuickchartClient::new()
+            .chart(r#"{"type":"bar"}"#.to_string())
+            .version_typed(version);
+        assert_eq!(client.get_version(), Some(token));
+        assert!(client.get_url().unwrap().ends_with(&format!("&v={}", token)));
+        assert_eq!(client.build_json_body()["version"], token);
+        assert_eq!(version.to_string(), token);
+    }
+}
+
+#[test]
+fn test_title_and_axis_labels() {
+    let chart = r#"{"type":"line","options":{"title":{"fontSize":18},"plugins":{"title":{"font":{"size":18}}}}}"#;
+    let client = QuickchartClient::new()
+        .chart(chart.to_string())
+        .title("Signups".to_string())
+        .x_axis_label("Month".to_string())
+        .y_axis_label("Count".to_string());
+
+    let options = client.build_json_body()["chart"]["options"].clone();
+    assert_eq!(
+        options["title"],
+        serde_json::json!({"fontSize": 18, "display": true, "text": "Signups"})
+    );
+    assert_eq!(
+        options["scales"]["xAxes"],
+        serde_json::json!([{"scaleLabel": {"display": true, "labelString": "Month"}}])
+    );
+    assert_eq!(options["scales"]["yAxes"][0]["scaleLabel"]["labelString"], "Count");
+
+    let options = client.version("4".to_string()).build_json_body()["chart"]["options"].clone();
+    assert_eq!(
+        options["plugins"]["title"],
+        serde_json::json!({"font": {"size": 18}, "display": true, "text": "Signups"})
+    );
+    assert_eq!(options["scales"]["x"]["title"], serde_json::json!({"display": true, "text": "Month"}));
+    assert_eq!(options["scales"]["y"]["title"]["text"], "Count");
+}
+
+#[test]
+fn test_from_url_round_trip() {
+    let original = QuickchartClient::new()
+        .base_url("http://localhost:3400/quickchart")
+        .unwrap()
+        .chart(r#"{"type":"bar","data":{"labels":["A & B"],"datasets":[{"data":[1]}]}}"#.to_string())
+        .width(800)
+        .height(400)
+        .device_pixel_ratio(1.5)
+        .background_color("#ffffff".to_string())
+        .format("svg".to_string())
+        .version("4".to_string())
+        .extra_param("theme".to_string(), "dark".to_string());
+    let url = original.get_url().unwrap();
+
+    let parsed = QuickchartClient::from_url(&url).unwrap();
+    assert_eq!(parsed.get_base_url(), "http://localhost:3400/quickchart/");
+    assert_eq!(parsed.resolved_chart(), original.resolved_chart());
+    assert_eq!((parsed.get_width(), parsed.get_height()), (Some(800), Some(400)));
+    assert_eq!(parsed.get_device_pixel_ratio(), Some(1.5));
+    assert_eq!(parsed.get_background_color(), Some("#ffffff"));
+    assert_eq!(parsed.get_format(), Some("svg"));
+    assert_eq!(parsed.get_version(), Some("4"));
+    assert_eq!(parsed.get_extra_params(), &[("theme".to_string(), "dark".to_string())]);
+    assert_eq!(parsed.get_url().unwrap(), url);
+
+    let encoded = original.base64_chart(true).get_url().unwrap();
+    let parsed = QuickchartClient::try_from(encoded.as_str()).unwrap();
+    assert!(parsed.get_base64_chart());
+    assert_eq!(parsed.get_url().unwrap(), encoded);
+}
+
+#[test]
+fn test_from_url_errors() {
+    assert!(matches!(
+        QuickchartClient::from_url("https://quickchart.io/chart?w=800"),
+        Err(QCError::EmptyChart)
+    ));
+    assert!(matches!(
+        QuickchartClient::from_url("https://quickchart.io/chart?c={}&w=wide"),
+        Err(QCError::InvalidUrlParam { ref name, ref value }) if name == "w" && value == "wide"
+    ));
+    assert!(matches!(
+        QuickchartClient::from_url("https://quickchart.io/chart?c=%%%&encoding=base64"),
+        Err(QCError::InvalidUrlParam { ref name, .. }) if name == "c"
+    ));
+    assert!(matches!(QuickchartClient::from_url("not a url"), Err(QCError::UrlParseError(_))));
+}
+
+#[cfg(feature = "gzip")]
+#[tokio::test]
+async fn test_gzip_response_is_decoded() {
+    use std::io::Write;
+
+    let svg = "<svg>".to_string() + &"<rect/>".repeat(1000) + "</svg>";
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(svg.as_bytes()).unwrap();
+    let gzipped = encoder.finish().unwrap();
+
+    let server = MockServer::start(vec![
+        MockResponse::new(200, gzipped.clone()).header("Content-Encoding", "gzip"),
+        MockResponse::new(200, svg.clone()),
+    ]);
+    let client = mock_client(&server);
+    assert_eq!(client.post().await.unwrap(), svg.as_bytes());
+    assert!(server.requests()[0].header("accept-encoding").unwrap().contains("gzip"));
+
+    let client = client.compression(false);
+    assert_eq!(client.post().await.unwrap(), svg.as_bytes());
+    assert!(server.requests()[1].header("accept-encoding").is_none());
+}
+
+#[tokio::test]
+async fn test_post_with_cancel() {
+    let server = MockServer::start(vec![
+        MockResponse::new(200, "image").delay(Duration::from_millis(500)),
+        MockResponse::new(200, "image"),
+    ]);
+    let client = mock_client(&server).fallback_image(b"placeholder".to_vec());
+
+    // Cancelling immediately sends no request
+    let cancelled = client.post_with_cancel(std::future::ready(())).await;
+    assert!(matches!(cancelled, Err(QCError::Cancelled)));
+    assert!(server.requests().is_empty());
+
+    let cancelled = client.post_with_cancel(tokio::time::sleep(Duration::from_millis(50))).await;
+    assert!(matches!(cancelled, Err(QCError::Cancelled)));
+
+    let image = client.post_with_cancel(std::future::pending()).await.unwrap();
+    assert_eq!(image, b"image");
+
+    let short_url = client.get_short_url_with_cancel(std::future::ready(())).await;
+    assert!(matches!(short_url, Err(QCError::Cancelled)));
+}
+
+#[test]
+fn test_compacted_chart_matches_url() {
+    let client = QuickchartClient::new()
+        .chart("{\n  \"type\": \"bar\",\n  \"data\": {\"labels\": [\"A B\"]}\n}".to_string())
+        .span_gaps(true);
+
+    let compacted = client.compacted_chart();
+    assert!(compacted.starts_with(r#"{"type":"bar","data":{"labels":["A B"]}"#));
+    let url = client.get_url_parsed().unwrap();
+    let (_, chart) = url.query_pairs().find(|(key, _)| key == "c").unwrap();
+    assert_eq!(chart, compacted);
+
+    let js = QuickchartClient::new().chart("{type: 'bar',\n\n data: {}}".to_string());
+    let url = js.get_url_parsed().unwrap();
+    let (_, chart) = url.query_pairs().find(|(key, _)| key == "c").unwrap();
+    assert_eq!(chart, js.compacted_chart());
+}
+
+#[tokio::test]
+async fn test_webp_and_pdf_formats() {
+    for (format, typed) in [("webp", crate::ImageFormat::WebP), ("pdf", crate::ImageFormat::Pdf)] {
+        let client = QuickchartClient::new()
+            .chart(r#"{"type":"bar"}"#.to_string())
+            .format_typed(typed);
+        assert_eq!(client.build_json_body()["format"], format);
+        assert!(client.get_url().unwrap().ends_with(&format!("&f={}", format)));
+        assert_eq!(crate::ImageFormat::from_extension(format), Some(typed));
+    }
+    assert_eq!(crate::ImageFormat::WebP.mime_type(), "image/webp");
+    assert_eq!(crate::ImageFormat::Pdf.mime_type(), "application/pdf");
+
+    let pdf = b"%PDF-1.4\n%\xe2\xe3\xcf\xd3\n".to_vec();
+    let server = MockServer::start(vec![MockResponse::new(200, pdf.clone())]);
+    let path = std::env::temp_dir().join(format!("quickchart-format-{}.pdf", std::process::id()));
+    mock_client(&server).format("pdf".to_string()).to_file(&path).await.unwrap();
+    assert_eq!(std::fs::read(&path).unwrap(), pdf);
+    assert_eq!(server.requests()[0].json()["format"], "pdf");
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_validate() {
+    let client = |chart: &str| QuickchartClient::new().chart(chart.to_string());
+
+    assert!(client(r#"{"type":"bar"}"#).validate().is_ok());
+    assert!(client("{type: 'bar', options: {onClick: function(e) { return e; }}}").validate().is_ok());
+    assert!(QuickchartClient::new().chart_value(serde_json::json!({})).validate().is_ok());
+    for value in [serde_json::json!(42), serde_json::json!(["bar"]), serde_json::Value::Null] {
+        assert!(matches!(
+            QuickchartClient::new().chart_value(value).validate(),
+            Err(QCError::InvalidChart(ref message)) if message == "the config is not a JSON object"
+        ));
+    }
+
+    assert!(matches!(client("  ").validate(), Err(QCError::EmptyChart)));
+    assert!(matches!(
+        client("[1, 2]").validate(),
+        Err(QCError::InvalidChart(ref message)) if message == "the config is not a JSON object"
+    ));
+    assert!(matches!(
+        client(r#"{"type":"bar","data":{}"#).validate(),
+        Err(QCError::InvalidChart(ref message)) if message.starts_with("unclosed '{'")
+    ));
+}
+
+#[test]
+fn test_space_encoding() {
+    let client = QuickchartClient::new()
+        .chart(r#"{"type":"bar","data":{"labels":["A B","1+1"]}}"#.to_string())
+        .background_color("rgb(1, 2, 3)".to_string());
+    assert_eq!(client.get_space_encoding(), crate::SpaceEncoding::Plus);
+
+    let plus = client.get_url().unwrap();
+    assert!(plus.contains("%22A+B%22") && plus.contains("bkg=rgb%281%2C+2%2C+3%29"));
+
+    let percent = client.space_encoding(crate::SpaceEncoding::Percent).get_url().unwrap();
+    assert!(percent.contains("%22A%20B%22") && percent.contains("bkg=rgb%281%2C%202%2C%203%29"));
+    // Literal plus signs stay encoded as %2B
+    assert!(percent.contains("%221%2B1%22"));
+    assert!(!percent.contains('+'));
+}
+
+#[tokio::test]
+async fn test_custom_headers() {
+    let server = MockServer::start(vec![MockResponse::new(200, "image")]);
+    let client = mock_client(&server)
+        .header("Authorization".to_string(), "Bearer abc123".to_string())
+        .header("X-Tag".to_string(), "one".to_string())
+        .header("X-Tag".to_string(), "two".to_string());
+    assert_eq!(client.get_headers().len(), 3);
+    assert!(!format!("{:?}", client).contains("abc123"));
+
+    client.post().await.unwrap();
+
+    let request = &server.requests()[0];
+    assert_eq!(request.header("authorization"), Some("Bearer abc123"));
+    assert_eq!(request.header("content-type"), Some("application/json"));
+    let tags: Vec<&str> = request
+        .headers
+        .iter()
+        .filter(|(name, _)| name.eq_ignore_ascii_case("x-tag"))
+        .map(|(_, value)| value.as_str())
+        .collect();
+    assert_eq!(tags, ["one", "two"]);
+}
+
+#[tokio::test]
+async fn test_custom_header_overrides_content_type() {
+    let server = MockServer::start(vec![MockResponse::new(200, "image")]);
+    let client = mock_client(&server)
+        .header("Content-Type".to_string(), "application/json; charset=utf-8".to_string());
+
+    client.post().await.unwrap();
+
+    let request = &server.requests()[0];
+    assert_eq!(request.header("content-type"), Some("application/json; charset=utf-8"));
+    let content_types = request.headers.iter().filter(|(n, _)| n.eq_ignore_ascii_case("content-type"));
+    assert_eq!(content_types.count(), 1);
+}
+
+#[tokio::test]
+async fn test_invalid_header() {
+    let server = MockServer::start(vec![MockResponse::new(200, "image")]);
+    for (name, value) in [("Bad Name", "value"), ("X-Ok", "line\nbreak")] {
+        let client = mock_client(&server).header(name.to_string(), value.to_string());
+        assert!(matches!(
+            client.post().await,
+            Err(QCError::InvalidHeader(ref header)) if header == name
+        ));
+    }
+    assert!(server.requests().is_empty());
+}
+
+#[test]
+fn test_build_request() {
+    let client = QuickchartClient::new()
+        .chart(r#"{"type":"bar","data":{"labels":["A"]}}"#.to_string())
+        .width(640)
+        .header("X-Request-Id".to_string(), "7f3a".to_string());
+
+    let request = client.build_request("chart/create").unwrap();
+
+    assert_eq!(request.method(), reqwest::Method::POST);
+    assert_eq!(request.url().as_str(), "https://quickchart.io/chart/create");
+    assert_eq!(request.headers()["content-type"], "application/json");
+    assert_eq!(request.headers()["x-request-id"], "7f3a");
+    let body = request.body().and_then(|body| body.as_bytes()).unwrap();
+    assert_eq!(body, serde_json::to_string(&client.build_json_body()).unwrap().as_bytes());
+
+    assert!(matches!(QuickchartClient::new().build_request("chart"), Err(QCError::EmptyChart)));
+}
+
+#[tokio::test]
+async fn test_short_url_non_json_response() {
+    let html = "<!DOCTYPE html><html><body><h1>Service Unavailable</h1></body></html>";
+    let server = MockServer::start(vec![
+        MockResponse::new(200, html).header("Content-Type", "text/html"),
+        MockResponse::new(200, b"\xff\xfe{\"url\":\"x\"}".to_vec()),
+    ]);
+    let client = mock_client(&server);
+
+    assert!(matches!(client.get_short_url().await, Err(QCError::JsonParseError(_))));
+    // Invalid UTF-8 isn't replaced and parsed as text
+    assert!(matches!(client.get_short_url().await, Err(QCError::JsonParseError(_))));
+}
+
+#[test]
+fn test_chart_typed_stores_value() {
+    let chart = crate::Chart::new(crate::ChartType::Line)
+        .labels(vec!["A".to_string()])
+        .dataset(crate::Dataset::new(vec![1.5]));
+    let client = QuickchartClient::new().chart_typed(chart.clone());
+
+    // Stored as a value, so it isn't parsed back from a string on every request
+    assert_eq!(client.chart_json, Some(serde_json::to_value(&chart).unwrap()));
+    assert!(client.chart.is_empty());
+    assert_eq!(client.build_json_body()["chart"]["data"]["datasets"][0]["data"][0], 1.5);
+}
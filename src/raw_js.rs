@@ -0,0 +1,84 @@
+use serde_json::Value;
+
+#[cfg(test)]
+#[path = "raw_js_test.rs"]
+mod tests;
+
+/// Prefix marking a JSON string as raw JavaScript. The NUL characters keep it from colliding
+/// with real config strings.
+const RAW_JS_MARKER: &str = "\u{0}quickchart-rs:raw-js\u{0}";
+
+/// A JavaScript snippet, such as a formatter callback, embedded verbatim in a chart config.
+///
+/// JSON can't carry functions, so a config containing raw JS is sent to QuickChart as
+/// JavaScript object notation instead of JSON.
+///
+/// # Example
+///
+/// ```
+/// use quickchart_rs::RawJs;
+///
+/// let formatter = RawJs::new("(value) => value + '%'".to_string());
+/// assert_eq!(formatter.as_str(), "(value) => value + '%'");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RawJs(String);
+
+impl RawJs {
+    pub fn new(code: String) -> Self {
+        RawJs(code)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Encode the snippet as a marker string that [`to_config_string`] emits unquoted.
+    pub(crate) fn to_value(&self) -> Value {
+        Value::String(format!("{}{}", RAW_JS_MARKER, self.0))
+    }
+}
+
+/// Serialize a chart config, emitting any embedded [`RawJs`] values verbatim. Configs without
+/// raw JS serialize to plain compact JSON.
+pub(crate) fn to_config_string(config: &Value) -> String {
+    let mut config = config.clone();
+    let mut snippets = Vec::new();
+    extract_snippets(&mut config, &mut snippets);
+
+    let mut serialized = config.to_string();
+    for (i, code) in snippets.iter().enumerate() {
+        serialized = serialized.replacen(&format!("\"{}\"", placeholder(i)), code, 1);
+    }
+    serialized
+}
+
+/// Whether the config embeds any [`RawJs`] values, i.e. must be sent as JS notation.
+pub(crate) fn contains_raw_js(value: &Value) -> bool {
+    match value {
+        Value::String(s) => s.starts_with(RAW_JS_MARKER),
+        Value::Array(items) => items.iter().any(contains_raw_js),
+        Value::Object(map) => map.values().any(contains_raw_js),
+        _ => false,
+    }
+}
+
+/// Replace raw JS marker strings with numbered placeholders, collecting their code in order.
+fn extract_snippets(value: &mut Value, snippets: &mut Vec<String>) {
+    match value {
+        Value::String(s) => {
+            if let Some(code) = s.strip_prefix(RAW_JS_MARKER) {
+                let code = code.to_string();
+                *s = placeholder(snippets.len());
+                snippets.push(code);
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(|item| extract_snippets(item, snippets)),
+        Value::Object(map) => map.values_mut().for_each(|item| extract_snippets(item, snippets)),
+        _ => {}
+    }
+}
+
+fn placeholder(index: usize) -> String {
+    format!("__quickchart_rs_raw_js_{}__", index)
+}
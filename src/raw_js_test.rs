@@ -0,0 +1,23 @@
+use super::*;
+
+#[test]
+fn test_to_config_string_without_raw_js() {
+    let config = serde_json::json!({ "type": "bar", "data": { "labels": ["A"] } });
+    assert_eq!(to_config_string(&config), config.to_string());
+}
+
+#[test]
+fn test_to_config_string_emits_raw_js_unquoted() {
+    let config = serde_json::json!({
+        "type": "bar",
+        "options": {
+            "first": RawJs::new("(v) => v + \"%\"".to_string()).to_value(),
+            "second": [RawJs::new("function() { return 1; }".to_string()).to_value()],
+        }
+    });
+
+    let serialized = to_config_string(&config);
+    assert!(serialized.contains(r#""first":(v) => v + "%""#));
+    assert!(serialized.contains(r#""second":[function() { return 1; }]"#));
+    assert!(!serialized.contains("__quickchart_rs_raw_js_"));
+}
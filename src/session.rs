@@ -0,0 +1,77 @@
+//! Sharing one HTTP connection pool across many charts.
+
+use crate::quickchart_client::QuickchartClient;
+use reqwest::Client;
+
+#[cfg(test)]
+#[path = "session_test.rs"]
+mod tests;
+
+/// A factory for [`QuickchartClient`]s that share one HTTP client, and with it one connection
+/// pool, for services that render many charts.
+///
+/// [`QuickchartClient::new()`] builds a fresh HTTP client every time, so creating a client per
+/// chart opens new connections (and repeats the TLS handshake) for each one. A session builds
+/// the HTTP client once; each client it hands out is a cheap copy of its template with the
+/// chart set, sending requests through the shared pool.
+///
+/// Settings on the template, such as the base URL, API key, timeout or retries, apply to every
+/// chart client. Settings changed on a chart client only affect that client, but changing its
+/// connection settings (e.g. [`timeout()`](QuickchartClient::timeout)) gives it a new HTTP
+/// client of its own. The blocking methods (`blocking` feature) still build a blocking HTTP
+/// client per chart client.
+///
+/// # Example
+///
+/// ```no_run
+/// # async fn run() -> Result<(), quickchart_rs::QCError> {
+/// use quickchart_rs::{QuickchartClient, QuickchartSession};
+///
+/// let session = QuickchartSession::from_template(QuickchartClient::new().retries(2));
+/// for sales in [[1, 2], [3, 4]] {
+///     let chart = format!(r#"{{"type":"bar","data":{{"datasets":[{{"data":{:?}}}]}}}}"#, sales);
+///     let image = session.chart(chart).width(800).post().await?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct QuickchartSession {
+    template: QuickchartClient,
+}
+
+impl QuickchartSession {
+    /// A session with default settings and a new HTTP client.
+    pub fn new() -> Self {
+        Self::from_template(QuickchartClient::new())
+    }
+
+    /// A session sending requests through `client`, configured as in
+    /// [`QuickchartClient::with_client()`].
+    pub fn with_client(client: Client) -> Self {
+        Self::from_template(QuickchartClient::with_client(client))
+    }
+
+    /// A session whose clients start as copies of `template`, sharing its HTTP client. Any chart
+    /// config on the template is replaced by [`chart()`](QuickchartSession::chart).
+    pub fn from_template(template: QuickchartClient) -> Self {
+        QuickchartSession { template }
+    }
+
+    /// A client for `chart` that shares this session's HTTP client and settings.
+    pub fn chart(&self, chart: String) -> QuickchartClient {
+        self.template.clone().chart(chart)
+    }
+
+    /// A client with this session's HTTP client and settings, for the other ways of setting a
+    /// chart, such as [`chart_value()`](QuickchartClient::chart_value).
+    pub fn client(&self) -> QuickchartClient {
+        self.template.clone()
+    }
+}
+
+impl Default for QuickchartSession {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,34 @@
+use super::*;
+use crate::test_util::{MockResponse, MockServer};
+
+#[tokio::test]
+async fn test_session_clients_share_settings() {
+    let server = MockServer::start(vec![MockResponse::new(200, "image")]);
+    let template = QuickchartClient::new()
+        .base_url(server.url())
+        .unwrap()
+        .api_key("secret".to_string())
+        .chart(r#"{"type":"line"}"#.to_string());
+    let session = QuickchartSession::from_template(template);
+
+    let first = session.chart(r#"{"type":"bar"}"#.to_string()).width(300);
+    let second = session.chart(r#"{"type":"pie"}"#.to_string());
+    assert_eq!(first.post().await.unwrap(), b"image");
+    assert_eq!(second.post().await.unwrap(), b"image");
+
+    let requests = server.requests();
+    assert_eq!(requests[0].json()["chart"]["type"], "bar");
+    assert_eq!(requests[0].json()["width"], 300);
+    assert_eq!(requests[1].json()["chart"]["type"], "pie");
+    // Settings changed on one chart client don't leak into the session
+    assert!(requests[1].json().get("width").is_none());
+    assert!(requests.iter().all(|request| request.json()["key"] == "secret"));
+
+    assert_eq!(session.client().get_chart(), r#"{"type":"line"}"#);
+}
+
+#[test]
+fn test_session_with_client() {
+    let session = QuickchartSession::with_client(Client::new());
+    assert!(format!("{:?}", session.client()).contains("custom_client: true"));
+}
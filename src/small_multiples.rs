@@ -0,0 +1,90 @@
+use crate::{QCError, QuickchartClient};
+use image::{ImageFormat, RgbaImage};
+use std::io::Cursor;
+
+#[cfg(test)]
+#[path = "small_multiples_test.rs"]
+mod tests;
+
+impl QuickchartClient {
+    /// Render each config as its own chart and composite them, left to right and top to
+    /// bottom, into a single PNG grid `cols` charts wide. Requires the `image` feature.
+    ///
+    /// QuickChart has no subplot support, so every chart is rendered separately with
+    /// [`post()`](QuickchartClient::post) using this client's settings (dimensions, device pixel
+    /// ratio, option helpers and so on) and the config swapped in, always as PNG. Each grid cell
+    /// is as large as the largest chart; smaller charts sit in the top-left of their cell and
+    /// unused cells are transparent.
+    ///
+    /// Returns [`QCError::InvalidData`] if `configs` is empty, `cols` is zero or a response isn't
+    /// a PNG, and the error of the first chart that fails to render otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn run() -> Result<(), quickchart_rs::QCError> {
+    /// use quickchart_rs::QuickchartClient;
+    ///
+    /// let configs = (1..=6)
+    ///     .map(|n| serde_json::json!({ "type": "bar", "data": { "datasets": [{ "data": [n] }] } }))
+    ///     .collect();
+    /// let grid = QuickchartClient::new()
+    ///     .width(300)
+    ///     .height(200)
+    ///     .small_multiples(configs, 3)
+    ///     .await?;
+    /// std::fs::write("grid.png", grid)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn small_multiples(
+        &self,
+        configs: Vec<serde_json::Value>,
+        cols: usize,
+    ) -> Result<Vec<u8>, QCError> {
+        let (cols, rows) = grid_shape(configs.len(), cols)?;
+
+        let mut charts = Vec::with_capacity(configs.len());
+        for (i, config) in configs.iter().enumerate() {
+            let image_bytes = self
+                .clone()
+                .chart(config.to_string())
+                .format("png".to_string())
+                .post()
+                .await?;
+            let chart = image::load_from_memory_with_format(&image_bytes, ImageFormat::Png)
+                .map_err(|e| QCError::InvalidData(format!("chart {} is not a PNG: {}", i, e)))?;
+            charts.push(chart.to_rgba8());
+        }
+
+        let cell_width = charts.iter().map(RgbaImage::width).max().unwrap_or(0);
+        let cell_height = charts.iter().map(RgbaImage::height).max().unwrap_or(0);
+        let mut grid = RgbaImage::new(cell_width * cols as u32, cell_height * rows as u32);
+        for (i, chart) in charts.iter().enumerate() {
+            let (col, row) = ((i % cols) as i64, (i / cols) as i64);
+            image::imageops::overlay(
+                &mut grid,
+                chart,
+                col * cell_width as i64,
+                row * cell_height as i64,
+            );
+        }
+
+        let mut bytes = Cursor::new(Vec::new());
+        grid.write_to(&mut bytes, ImageFormat::Png)
+            .map_err(|e| QCError::InvalidData(format!("failed to encode grid: {}", e)))?;
+        Ok(bytes.into_inner())
+    }
+}
+
+/// The `(columns, rows)` of a grid holding `count` charts at most `cols` wide. Fewer charts than
+/// `cols` give a single row only as wide as needed.
+fn grid_shape(count: usize, cols: usize) -> Result<(usize, usize), QCError> {
+    if count == 0 {
+        return Err(QCError::InvalidData("no charts to lay out".to_string()));
+    }
+    if cols == 0 {
+        return Err(QCError::InvalidData("cols must be at least 1".to_string()));
+    }
+    Ok((cols.min(count), count.div_ceil(cols)))
+}
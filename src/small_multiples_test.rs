@@ -0,0 +1,50 @@
+use super::*;
+use crate::test_util::{MockResponse, MockServer};
+use image::Rgba;
+use reqwest::Url;
+
+fn encode_png(image: RgbaImage) -> Vec<u8> {
+    let mut bytes = Cursor::new(Vec::new());
+    image.write_to(&mut bytes, ImageFormat::Png).unwrap();
+    bytes.into_inner()
+}
+
+#[test]
+fn test_grid_shape() {
+    assert_eq!(grid_shape(6, 3).unwrap(), (3, 2));
+    assert_eq!(grid_shape(7, 3).unwrap(), (3, 3));
+    assert_eq!(grid_shape(1, 1).unwrap(), (1, 1));
+    assert_eq!(grid_shape(2, 4).unwrap(), (2, 1));
+    assert!(matches!(grid_shape(0, 3), Err(QCError::InvalidData(_))));
+    assert!(matches!(grid_shape(3, 0), Err(QCError::InvalidData(_))));
+}
+
+#[tokio::test]
+async fn test_small_multiples_composites_grid() {
+    let red = Rgba([255, 0, 0, 255]);
+    let blue = Rgba([0, 0, 255, 255]);
+    let server = MockServer::start(vec![
+        MockResponse::new(200, encode_png(RgbaImage::from_pixel(4, 3, red))),
+        MockResponse::new(200, encode_png(RgbaImage::from_pixel(4, 3, blue))),
+        MockResponse::new(200, encode_png(RgbaImage::from_pixel(2, 2, red))),
+    ]);
+    let mut client = QuickchartClient::new().width(4).height(3).format("svg".to_string());
+    client.base_url = Url::parse(server.url()).unwrap();
+
+    let configs = (0..3).map(|n| serde_json::json!({ "type": "bar", "data": [n] })).collect();
+    let grid = client.small_multiples(configs, 2).await.unwrap();
+    let grid = image::load_from_memory_with_format(&grid, ImageFormat::Png).unwrap().to_rgba8();
+
+    assert_eq!(grid.dimensions(), (8, 6));
+    assert_eq!(*grid.get_pixel(0, 0), red);
+    assert_eq!(*grid.get_pixel(4, 0), blue);
+    assert_eq!(*grid.get_pixel(1, 4), red);
+    assert_eq!(*grid.get_pixel(3, 5), Rgba([0, 0, 0, 0]));
+    assert_eq!(*grid.get_pixel(7, 5), Rgba([0, 0, 0, 0]));
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 3);
+    assert_eq!(requests[1].json()["chart"]["data"], serde_json::json!([1]));
+    assert_eq!(requests[1].json()["format"], "png");
+    assert_eq!(requests[1].json()["width"], 4);
+}
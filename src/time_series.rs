@@ -0,0 +1,48 @@
+use crate::chart::ChartType;
+use crate::QuickchartClient;
+use chrono::{DateTime, SecondsFormat, Utc};
+
+#[cfg(test)]
+#[path = "time_series_test.rs"]
+mod tests;
+
+impl QuickchartClient {
+    /// Build a single-series chart of `(timestamp, value)` points on a time-scale x axis, e.g.
+    /// for trading or monitoring dashboards. Requires the `chrono` feature.
+    ///
+    /// Each point becomes `{"x": "<timestamp>", "y": value}` with the timestamp as an RFC 3339
+    /// UTC string such as `"2024-03-01T09:30:00.000Z"`, which Chart.js parses unambiguously. The
+    /// x axis is set to `type: "time"` in the layout of the configured Chart.js version.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use chrono::{TimeZone, Utc};
+    /// use quickchart_rs::{ChartType, QuickchartClient};
+    ///
+    /// let points = [
+    ///     (Utc.with_ymd_and_hms(2024, 3, 1, 9, 30, 0).unwrap(), 101.5),
+    ///     (Utc.with_ymd_and_hms(2024, 3, 1, 9, 31, 0).unwrap(), 102.25),
+    /// ];
+    /// let client = QuickchartClient::from_time_series(&points, ChartType::Line);
+    /// ```
+    pub fn from_time_series(points: &[(DateTime<Utc>, f64)], chart_type: ChartType) -> Self {
+        let data: Vec<serde_json::Value> = points
+            .iter()
+            .map(|(timestamp, value)| {
+                serde_json::json!({
+                    "x": timestamp.to_rfc3339_opts(SecondsFormat::Millis, true),
+                    "y": value,
+                })
+            })
+            .collect();
+
+        let chart = serde_json::json!({
+            "type": chart_type.as_str(),
+            "data": { "datasets": [{ "data": data }] },
+        });
+        let mut client = QuickchartClient::new().chart(chart.to_string());
+        client.options.time_x_axis = true;
+        client
+    }
+}
@@ -0,0 +1,25 @@
+use super::*;
+use chrono::TimeZone;
+
+#[test]
+fn test_from_time_series() {
+    let points = [
+        (Utc.with_ymd_and_hms(2024, 3, 1, 9, 30, 0).unwrap(), 101.5),
+        (Utc.timestamp_millis_opt(1_709_285_460_250).unwrap(), 102.0),
+    ];
+
+    let client = QuickchartClient::from_time_series(&points, ChartType::Scatter);
+    let chart = &client.build_json_body()["chart"];
+    assert_eq!(chart["type"], "scatter");
+    assert_eq!(
+        chart["data"]["datasets"][0]["data"],
+        serde_json::json!([
+            { "x": "2024-03-01T09:30:00.000Z", "y": 101.5 },
+            { "x": "2024-03-01T09:31:00.250Z", "y": 102.0 },
+        ])
+    );
+    assert_eq!(chart["options"]["scales"]["xAxes"][0]["type"], "time");
+
+    let chart = &client.version("4".to_string()).build_json_body()["chart"];
+    assert_eq!(chart["options"]["scales"]["x"]["type"], "time");
+}
@@ -0,0 +1,96 @@
+use crate::{QCError, QuickchartClient};
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+#[cfg(test)]
+#[path = "zip_archive_test.rs"]
+mod tests;
+
+/// What [`QuickchartClient::charts_to_zip`] does when a chart fails to render.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OnChartError {
+    /// Stop at the first failure, remove the partly written archive and return the error. The
+    /// default.
+    #[default]
+    Abort,
+    /// Leave the chart out of the archive and report it in the returned list.
+    Skip,
+}
+
+impl QuickchartClient {
+    /// Render each `(name, client)` pair and write the images into a zip archive at `path`, one
+    /// entry per chart named `<name>.<format>` (`.png` when no format is set). Charts are
+    /// rendered one after another with [`post()`](QuickchartClient::post). Requires the `zip`
+    /// feature.
+    ///
+    /// Returns the charts skipped under [`OnChartError::Skip`] with their errors, which is
+    /// always empty under [`OnChartError::Abort`]. Errors creating or writing the archive are
+    /// returned as [`QCError::IoError`]. Whatever the error, the partly written archive is
+    /// removed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn run() -> Result<(), quickchart_rs::QCError> {
+    /// use quickchart_rs::{OnChartError, QuickchartClient};
+    ///
+    /// let charts = vec![
+    ///     ("sales".to_string(), QuickchartClient::new().chart("{type:'bar'}".to_string())),
+    ///     ("costs".to_string(), QuickchartClient::new().chart("{type:'line'}".to_string())),
+    /// ];
+    /// let skipped =
+    ///     QuickchartClient::charts_to_zip(&charts, "charts.zip", OnChartError::Skip).await?;
+    /// for (name, error) in skipped {
+    ///     eprintln!("{} failed: {}", name, error);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn charts_to_zip(
+        charts: &[(String, QuickchartClient)],
+        path: impl AsRef<Path>,
+        on_error: OnChartError,
+    ) -> Result<Vec<(String, QCError)>, QCError> {
+        let path = path.as_ref();
+        let archive = ZipWriter::new(File::create(path)?);
+        let result = write_charts(charts, archive, on_error).await;
+        if result.is_err() {
+            // Cleanup is best effort: the error worth reporting is the one that stopped the write
+            let _ = std::fs::remove_file(path);
+        }
+        result
+    }
+}
+
+/// Write the charts into `archive` and finish it, closing the file whether or not it succeeds.
+async fn write_charts(
+    charts: &[(String, QuickchartClient)],
+    mut archive: ZipWriter<File>,
+    on_error: OnChartError,
+) -> Result<Vec<(String, QCError)>, QCError> {
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+    let mut skipped = Vec::new();
+
+    for (name, client) in charts {
+        let image_bytes = match client.post().await {
+            Ok(image_bytes) => image_bytes,
+            Err(e) if on_error == OnChartError::Skip => {
+                skipped.push((name.clone(), e));
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+
+        let extension = client.resolved_format().unwrap_or("png").to_ascii_lowercase();
+        archive
+            .start_file(format!("{}.{}", name, extension), options)
+            .map_err(std::io::Error::from)?;
+        archive.write_all(&image_bytes)?;
+    }
+
+    archive.finish().map_err(std::io::Error::from)?;
+    Ok(skipped)
+}
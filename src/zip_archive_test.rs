@@ -0,0 +1,86 @@
+use super::*;
+use crate::test_util::{MockResponse, MockServer};
+use reqwest::Url;
+use std::io::Read;
+
+fn mock_chart(server: &MockServer, chart: &str) -> QuickchartClient {
+    let mut client = QuickchartClient::new().chart(chart.to_string());
+    client.base_url = Url::parse(server.url()).unwrap();
+    client
+}
+
+fn temp_zip_path(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("quickchart-{}-{}.zip", name, std::process::id()))
+}
+
+#[tokio::test]
+async fn test_charts_to_zip() {
+    let server = MockServer::start(vec![
+        MockResponse::new(200, "png-bytes"),
+        MockResponse::new(200, "svg-bytes"),
+    ]);
+    let charts = vec![
+        ("sales".to_string(), mock_chart(&server, r#"{"type":"bar"}"#)),
+        ("costs".to_string(), mock_chart(&server, r#"{"type":"line"}"#).format("SVG".to_string())),
+    ];
+    let path = temp_zip_path("two-entries");
+
+    let skipped = QuickchartClient::charts_to_zip(&charts, &path, OnChartError::Abort)
+        .await
+        .unwrap();
+    assert!(skipped.is_empty());
+
+    let mut archive = zip::ZipArchive::new(File::open(&path).unwrap()).unwrap();
+    assert_eq!(archive.len(), 2);
+    for (entry_name, expected) in [("sales.png", "png-bytes"), ("costs.svg", "svg-bytes")] {
+        let mut contents = String::new();
+        archive.by_name(entry_name).unwrap().read_to_string(&mut contents).unwrap();
+        assert_eq!(contents, expected);
+    }
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn test_charts_to_zip_skip_and_abort() {
+    let server = MockServer::start(vec![
+        MockResponse::new(400, "bad chart"),
+        MockResponse::new(200, "image"),
+    ]);
+    let charts = vec![
+        ("broken".to_string(), mock_chart(&server, r#"{"type":"bar"}"#)),
+        ("fine".to_string(), mock_chart(&server, r#"{"type":"bar"}"#)),
+    ];
+    let path = temp_zip_path("skip");
+
+    let skipped = QuickchartClient::charts_to_zip(&charts, &path, OnChartError::Skip)
+        .await
+        .unwrap();
+    assert_eq!(skipped.len(), 1);
+    assert_eq!(skipped[0].0, "broken");
+    let archive = zip::ZipArchive::new(File::open(&path).unwrap()).unwrap();
+    assert_eq!(archive.file_names().collect::<Vec<_>>(), vec!["fine.png"]);
+    std::fs::remove_file(&path).unwrap();
+
+    let server = MockServer::start(vec![MockResponse::new(400, "bad chart")]);
+    let charts = vec![("broken".to_string(), mock_chart(&server, r#"{"type":"bar"}"#))];
+    let path = temp_zip_path("abort");
+
+    let result = QuickchartClient::charts_to_zip(&charts, &path, OnChartError::Abort).await;
+    assert!(matches!(result, Err(QCError::ApiError { status: 400, .. })));
+    assert!(!path.exists());
+}
+
+#[tokio::test]
+async fn test_charts_to_zip_removes_archive_on_write_error() {
+    let server = MockServer::start(vec![MockResponse::new(200, "image")]);
+    // Duplicate entry names make the second start_file fail
+    let charts = vec![
+        ("same".to_string(), mock_chart(&server, r#"{"type":"bar"}"#)),
+        ("same".to_string(), mock_chart(&server, r#"{"type":"bar"}"#)),
+    ];
+    let path = temp_zip_path("duplicate");
+
+    let result = QuickchartClient::charts_to_zip(&charts, &path, OnChartError::Skip).await;
+    assert!(matches!(result, Err(QCError::IoError(_))));
+    assert!(!path.exists());
+}